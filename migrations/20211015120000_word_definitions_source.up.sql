@@ -0,0 +1,2 @@
+-- Add up migration script here
+ALTER TABLE word_definitions ADD COLUMN source VARCHAR(255) NULL;
@@ -0,0 +1,2 @@
+-- Add down migration script here
+ALTER TABLE words DROP COLUMN load_fail_reason;
@@ -0,0 +1,2 @@
+-- Add up migration script here
+ALTER TABLE words ADD COLUMN load_fail_reason VARCHAR(1000) NULL;
@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP TABLE word_relations;
+DROP TABLE word_definition_examples;
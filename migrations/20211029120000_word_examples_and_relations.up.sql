@@ -0,0 +1,23 @@
+-- Add up migration script here
+CREATE TABLE word_definition_examples (
+    id SERIAL PRIMARY KEY,
+    example VARCHAR(2000) NOT NULL,
+    "order" INT NOT NULL,
+    word_definition_id INT NOT NULL,
+    CONSTRAINT word_definition_examples_word_definition_id_fkey
+        FOREIGN KEY (word_definition_id)
+        REFERENCES word_definitions (id)
+);
+CREATE INDEX word_definition_examples_word_definition_id_index ON word_definition_examples (word_definition_id);
+
+CREATE TABLE word_relations (
+    id SERIAL PRIMARY KEY,
+    kind VARCHAR(255) NOT NULL,
+    body VARCHAR(255) NOT NULL,
+    "order" INT NOT NULL,
+    word_id INT NOT NULL,
+    CONSTRAINT word_relations_word_id_fkey
+        FOREIGN KEY (word_id)
+        REFERENCES words (id)
+);
+CREATE INDEX word_relations_word_id_index ON word_relations (word_id);
@@ -0,0 +1,6 @@
+-- Add down migration script here
+DROP INDEX words_part_of_speech_index;
+ALTER TABLE words
+    DROP COLUMN syllables,
+    DROP COLUMN gender,
+    DROP COLUMN part_of_speech;
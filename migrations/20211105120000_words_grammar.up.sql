@@ -0,0 +1,6 @@
+-- Add up migration script here
+ALTER TABLE words
+    ADD COLUMN part_of_speech VARCHAR(255) NULL,
+    ADD COLUMN gender VARCHAR(255) NULL,
+    ADD COLUMN syllables VARCHAR(255) NULL;
+CREATE INDEX words_part_of_speech_index ON words (part_of_speech);
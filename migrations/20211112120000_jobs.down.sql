@@ -0,0 +1,4 @@
+-- Add down migration script here
+DROP TABLE job_errors;
+DROP TABLE jobs;
+DROP TABLE bulk_loads;
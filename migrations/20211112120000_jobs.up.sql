@@ -0,0 +1,48 @@
+-- Add up migration script here
+-- Words of a bulk load are loaded by jobs, the counters are its progress
+CREATE TABLE bulk_loads (
+    id SERIAL PRIMARY KEY,
+    state VARCHAR(255) NOT NULL,
+    source VARCHAR(255) NOT NULL,
+    total INT NOT NULL,
+    processed INT NOT NULL,
+    loaded INT NOT NULL,
+    failed INT NOT NULL,
+    last_errors VARCHAR(2000)[] NOT NULL,
+    requests_per_second INT NOT NULL,
+    concurrency INT NOT NULL,
+    started_at TIMESTAMP(0) NOT NULL,
+    finished_at TIMESTAMP(0) NULL
+);
+
+CREATE TABLE jobs (
+    id SERIAL PRIMARY KEY,
+    kind VARCHAR(255) NOT NULL,
+    payload TEXT NOT NULL,
+    status VARCHAR(255) NOT NULL,
+    attempts INT NOT NULL,
+    max_attempts INT NOT NULL,
+    run_at TIMESTAMP(0) NOT NULL,
+    locked_at TIMESTAMP(0) NULL,
+    created_at TIMESTAMP(0) NOT NULL,
+    finished_at TIMESTAMP(0) NULL,
+    last_error VARCHAR(2000) NULL,
+    bulk_load_id INT NULL,
+    CONSTRAINT jobs_bulk_load_id_fkey
+        FOREIGN KEY (bulk_load_id)
+        REFERENCES bulk_loads (id)
+);
+CREATE INDEX jobs_status_run_at_index ON jobs (status, run_at);
+CREATE INDEX jobs_bulk_load_id_index ON jobs (bulk_load_id);
+
+CREATE TABLE job_errors (
+    id SERIAL PRIMARY KEY,
+    job_id INT NOT NULL,
+    attempt INT NOT NULL,
+    error VARCHAR(2000) NOT NULL,
+    created_at TIMESTAMP(0) NOT NULL,
+    CONSTRAINT job_errors_job_id_fkey
+        FOREIGN KEY (job_id)
+        REFERENCES jobs (id)
+);
+CREATE INDEX job_errors_job_id_index ON job_errors (job_id);
@@ -0,0 +1,13 @@
+-- Add up migration script here
+CREATE TABLE raw_pages (
+    id SERIAL PRIMARY KEY,
+    word_id INT NOT NULL,
+    source VARCHAR(255) NOT NULL,
+    content TEXT NOT NULL,
+    content_hash VARCHAR(64) NOT NULL,
+    fetched_at TIMESTAMP(0) NOT NULL,
+    CONSTRAINT raw_pages_word_id_fkey
+        FOREIGN KEY (word_id)
+        REFERENCES words (id)
+);
+CREATE UNIQUE INDEX raw_pages_word_id_source_index ON raw_pages (word_id, source);
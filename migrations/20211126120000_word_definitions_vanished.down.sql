@@ -0,0 +1,2 @@
+-- Add down migration script here
+ALTER TABLE word_definitions DROP COLUMN is_vanished;
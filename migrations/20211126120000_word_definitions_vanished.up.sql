@@ -0,0 +1,2 @@
+-- Add up migration script here
+ALTER TABLE word_definitions ADD COLUMN is_vanished BOOLEAN NOT NULL DEFAULT false;
@@ -0,0 +1,7 @@
+-- Add down migration script here
+DROP INDEX words_load_fail_kind_index;
+ALTER TABLE words
+    DROP COLUMN load_fail_attempts,
+    DROP COLUMN load_fail_stage,
+    DROP COLUMN load_fail_http_status,
+    DROP COLUMN load_fail_kind;
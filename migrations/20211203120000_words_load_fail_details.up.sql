@@ -0,0 +1,7 @@
+-- Add up migration script here
+ALTER TABLE words
+    ADD COLUMN load_fail_kind VARCHAR(255) NULL,
+    ADD COLUMN load_fail_http_status INT NULL,
+    ADD COLUMN load_fail_stage VARCHAR(255) NULL,
+    ADD COLUMN load_fail_attempts INT NOT NULL DEFAULT 0;
+CREATE INDEX words_load_fail_kind_index ON words (load_fail_kind);
@@ -0,0 +1,5 @@
+-- Add down migration script here
+DROP INDEX words_load_status_synced_at_index;
+ALTER TABLE words
+    DROP COLUMN has_upstream_changes,
+    DROP COLUMN synced_at;
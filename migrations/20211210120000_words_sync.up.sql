@@ -0,0 +1,5 @@
+-- Add up migration script here
+ALTER TABLE words
+    ADD COLUMN synced_at TIMESTAMP(0) NULL,
+    ADD COLUMN has_upstream_changes BOOLEAN NOT NULL DEFAULT false;
+CREATE INDEX words_load_status_synced_at_index ON words (load_status, synced_at);
@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP INDEX words_tags_index;
+ALTER TABLE words DROP COLUMN tags;
@@ -0,0 +1,3 @@
+-- Add up migration script here
+ALTER TABLE words ADD COLUMN tags VARCHAR(255)[] NOT NULL DEFAULT '{}';
+CREATE INDEX words_tags_index ON words USING GIN (tags);
@@ -0,0 +1,18 @@
+-- Add up migration script here
+-- Revisions without a user are made by loads, syncs and reparses
+CREATE TABLE word_revisions (
+    id SERIAL PRIMARY KEY,
+    word_id INT NOT NULL,
+    user_id INT NULL,
+    action VARCHAR(255) NOT NULL,
+    before TEXT NULL,
+    after TEXT NOT NULL,
+    created_at TIMESTAMP(0) NOT NULL,
+    CONSTRAINT word_revisions_word_id_fkey
+        FOREIGN KEY (word_id)
+        REFERENCES words (id),
+    CONSTRAINT word_revisions_user_id_fkey
+        FOREIGN KEY (user_id)
+        REFERENCES users (id)
+);
+CREATE INDEX word_revisions_word_id_index ON word_revisions (word_id);
@@ -0,0 +1,7 @@
+-- Add down migration script here
+DROP INDEX word_definitions_definition_tsv_index;
+DROP INDEX words_body_yo_trgm_index;
+DROP INDEX words_body_lower_trgm_index;
+DROP INDEX words_body_plain_trgm_index;
+DROP INDEX words_body_trgm_index;
+DROP EXTENSION IF EXISTS pg_trgm;
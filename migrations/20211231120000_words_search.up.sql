@@ -0,0 +1,13 @@
+-- Add up migration script here
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+-- The search folds case and ё only when asked, every form it can compare has its own index
+CREATE INDEX words_body_trgm_index
+    ON words USING GIN (translate(lower(body), 'Ёё', 'Ее') gin_trgm_ops);
+CREATE INDEX words_body_plain_trgm_index
+    ON words USING GIN (body gin_trgm_ops);
+CREATE INDEX words_body_lower_trgm_index
+    ON words USING GIN (lower(body) gin_trgm_ops);
+CREATE INDEX words_body_yo_trgm_index
+    ON words USING GIN (translate(body, 'Ёё', 'Ее') gin_trgm_ops);
+CREATE INDEX word_definitions_definition_tsv_index
+    ON word_definitions USING GIN (to_tsvector('russian', translate(definition, 'Ёё', 'Ее')));
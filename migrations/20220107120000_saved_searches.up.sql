@@ -0,0 +1,14 @@
+-- Add up migration script here
+CREATE TABLE saved_searches (
+    id SERIAL PRIMARY KEY,
+    user_id INT NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    filter TEXT NOT NULL,
+    field_orders TEXT NOT NULL,
+    created_at TIMESTAMP(0) NOT NULL,
+    updated_at TIMESTAMP(0) NOT NULL,
+    CONSTRAINT saved_searches_user_id_fkey
+        FOREIGN KEY (user_id)
+        REFERENCES users (id),
+    CONSTRAINT saved_searches_user_id_name_key UNIQUE (user_id, name)
+);
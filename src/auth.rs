@@ -4,19 +4,16 @@ use rocket::request::{FromRequest, Outcome, Request};
 use crate::error::VortoResult;
 use crate::services::jwt_service;
 
-pub struct Admin;
+pub struct Admin {
+    pub user_id: i32,
+}
 
 fn get_jwt_token<'r>(request: &'r Request<'_>) -> Option<String> {
     request
         .headers()
         .get_one("Authorization")
-        .and_then(|auth_value| {
-            if auth_value.starts_with("Bearer ") {
-                Some(auth_value[7..].to_owned())
-            } else {
-                None
-            }
-        })
+        .and_then(|auth_value| auth_value.strip_prefix("Bearer "))
+        .map(|token| token.to_owned())
 }
 
 #[rocket::async_trait]
@@ -25,8 +22,8 @@ impl<'r> FromRequest<'r> for Admin {
 
     async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
         if let Some(jwt_token) = get_jwt_token(request) {
-            if let VortoResult::Ok(_) = jwt_service::verify(&jwt_token) {
-                Outcome::Success(Admin {})
+            if let VortoResult::Ok(user_id) = jwt_service::verify(&jwt_token) {
+                Outcome::Success(Admin { user_id })
             } else {
                 Outcome::Failure((Status::Unauthorized, ()))
             }
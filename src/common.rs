@@ -1,12 +1,22 @@
 use std::{
     collections::HashMap,
+    env,
     hash::Hash,
+    str::FromStr,
 };
 
 use indexmap::IndexMap;
 
 use crate::error::{VortoError, VortoErrorCode, VortoResult};
 
+// Value of an environment variable, the default when it is not set or can't be parsed
+pub fn env_or<T: FromStr>(name: &str, default: T) -> T {
+    env::var(name)
+        .ok()
+        .and_then(|v| v.parse::<T>().ok())
+        .unwrap_or(default)
+}
+
 pub fn group<T, G, K, V>(
     datas: &Vec<T>,
     group_fn: impl Fn(&T) -> &G,
@@ -0,0 +1,89 @@
+use sqlx::{query, query_as, PgPool, Postgres, Transaction};
+
+use crate::{domain::bulk_load::BulkLoad, error::VortoResult};
+
+pub async fn insert(
+    bulk_load: &BulkLoad,
+    pool: &PgPool,
+    tx: Option<&mut Transaction<'_, Postgres>>,
+) -> VortoResult<i32> {
+    let qry = query!(
+        r#"
+        INSERT INTO bulk_loads
+            (state, source, total, processed, loaded, failed, last_errors, requests_per_second,
+            concurrency, started_at, finished_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        RETURNING id
+        "#,
+        bulk_load.state,
+        bulk_load.source,
+        bulk_load.total,
+        bulk_load.processed,
+        bulk_load.loaded,
+        bulk_load.failed,
+        &bulk_load.last_errors,
+        bulk_load.requests_per_second,
+        bulk_load.concurrency,
+        bulk_load.started_at,
+        bulk_load.finished_at
+    )
+    .map(|r| r.id);
+
+    VortoResult::Ok(run_qry!(qry, fetch_one, pool, tx))
+}
+
+pub async fn update(
+    bulk_load: &BulkLoad,
+    pool: &PgPool,
+    tx: Option<&mut Transaction<'_, Postgres>>,
+) -> VortoResult<()> {
+    let qry = query!(
+        r#"
+        UPDATE bulk_loads
+        SET state = $1,
+            processed = $2,
+            loaded = $3,
+            failed = $4,
+            last_errors = $5,
+            finished_at = $6
+        WHERE id = $7
+        "#,
+        bulk_load.state,
+        bulk_load.processed,
+        bulk_load.loaded,
+        bulk_load.failed,
+        &bulk_load.last_errors,
+        bulk_load.finished_at,
+        bulk_load.id
+    );
+
+    run_qry!(qry, execute, pool, tx);
+
+    VortoResult::Ok(())
+}
+
+// Only the last bulk load is shown, one runs at a time
+pub async fn get_last(
+    pool: &PgPool,
+    tx: Option<&mut Transaction<'_, Postgres>>,
+) -> VortoResult<Option<BulkLoad>> {
+    let qry = query_as!(
+        BulkLoad,
+        "SELECT * FROM bulk_loads ORDER BY id DESC LIMIT 1"
+    );
+
+    VortoResult::Ok(run_qry!(qry, fetch_optional, pool, tx))
+}
+
+// The row stays locked until the transaction ends, results of its jobs are added one by one
+pub async fn lock_by_id(id: i32, tx: &mut Transaction<'_, Postgres>) -> VortoResult<BulkLoad> {
+    let bulk_load = query_as!(
+        BulkLoad,
+        "SELECT * FROM bulk_loads WHERE id = $1 FOR UPDATE",
+        id
+    )
+    .fetch_one(tx)
+    .await?;
+
+    VortoResult::Ok(bulk_load)
+}
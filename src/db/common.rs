@@ -1,22 +1,56 @@
-use std::{any::Any, fmt::Display};
+use sqlx::{
+    encode::Encode,
+    postgres::{PgArguments, PgRow},
+    query::QueryAs,
+    query_as_with, Arguments, FromRow, Postgres, Type,
+};
 
-pub fn to_sql_value<T: Any + Display>(value: &T) -> String {
-    let value_any = value as &dyn Any;
+// Query text with its values bound as $n parameters, so no value ends up in the text
+#[derive(Default)]
+pub struct QryBuilder {
+    sql: String,
+    args: PgArguments,
+    arg_count: usize,
+}
 
-    if value_any.is::<String>() || value_any.is::<&str>() {
-        //SQL injection protect        
-        format!("'{}'", value.to_string().replace("'", "''"))
-    } else {
-        value.to_string()
+impl QryBuilder {
+    pub fn new(sql: &str) -> Self {
+        QryBuilder {
+            sql: sql.to_owned(),
+            ..Default::default()
+        }
+    }
+
+    pub fn push(&mut self, sql: &str) -> &mut Self {
+        self.sql.push_str(sql);
+        self
     }
-}
 
-pub fn in_qry<T: Any + Display>(field: &str, values: &Vec<T>) -> String {
-    format!(
-        "{} IN ({})",
-        field,
-        values.into_iter().map(to_sql_value).collect::<Vec<_>>().join(",")
-    )
+    pub fn push_bind<'q, T: 'q + Encode<'q, Postgres> + Type<Postgres> + Send>(
+        &mut self,
+        value: T,
+    ) -> &mut Self {
+        self.args.add(value);
+        self.arg_count += 1;
+        self.sql.push_str(&format!("${}", self.arg_count));
+        self
+    }
+
+    // The whole list goes as one array parameter
+    pub fn push_in<'q, T: 'q + Encode<'q, Postgres> + Type<Postgres> + Send>(
+        &mut self,
+        field: &str,
+        values: T,
+    ) -> &mut Self {
+        self.push(field).push(" = ANY(").push_bind(values).push(")")
+    }
+
+    pub fn build_as<T>(&mut self) -> QueryAs<'_, Postgres, T, PgArguments>
+    where
+        T: for<'r> FromRow<'r, PgRow>,
+    {
+        query_as_with(&self.sql, std::mem::take(&mut self.args))
+    }
 }
 
 macro_rules! run_qry {
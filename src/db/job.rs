@@ -0,0 +1,280 @@
+use chrono::NaiveDateTime;
+use sqlx::{query, query_as, PgPool, Postgres, Transaction};
+
+use crate::{
+    db::common::*,
+    domain::{
+        enums::{JobKind, JobStatus},
+        job::{Job, JobError},
+    },
+    error::{VortoError, VortoErrorCode, VortoResult},
+};
+
+fn job_not_found<T>() -> VortoResult<T> {
+    VortoResult::Err(VortoError::new(
+        VortoErrorCode::NotFound,
+        "Job not found".to_owned(),
+    ))
+}
+
+pub async fn insert(
+    job: &Job,
+    pool: &PgPool,
+    tx: Option<&mut Transaction<'_, Postgres>>,
+) -> VortoResult<i32> {
+    let qry = query!(
+        r#"
+        INSERT INTO jobs
+            (kind, payload, status, attempts, max_attempts, run_at, locked_at, created_at, finished_at, last_error, bulk_load_id)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        RETURNING id
+        "#,
+        job.kind,
+        job.payload,
+        job.status,
+        job.attempts,
+        job.max_attempts,
+        job.run_at,
+        job.locked_at,
+        job.created_at,
+        job.finished_at,
+        job.last_error,
+        job.bulk_load_id
+    )
+    .map(|r| r.id);
+
+    VortoResult::Ok(run_qry!(qry, fetch_one, pool, tx))
+}
+
+pub async fn update(
+    job: &Job,
+    pool: &PgPool,
+    tx: Option<&mut Transaction<'_, Postgres>>,
+) -> VortoResult<()> {
+    let qry = query!(
+        r#"
+        UPDATE jobs
+        SET status = $1,
+            attempts = $2,
+            run_at = $3,
+            locked_at = $4,
+            finished_at = $5,
+            last_error = $6
+        WHERE id = $7
+        "#,
+        job.status,
+        job.attempts,
+        job.run_at,
+        job.locked_at,
+        job.finished_at,
+        job.last_error,
+        job.id
+    );
+
+    run_qry!(qry, execute, pool, tx);
+
+    VortoResult::Ok(())
+}
+
+// Nothing is updated when somebody has changed the status meanwhile,
+// e.g. the result of a job cancelled while running is not saved
+pub async fn update_if_status(
+    job: &Job,
+    statuses: &[JobStatus],
+    pool: &PgPool,
+    tx: Option<&mut Transaction<'_, Postgres>>,
+) -> VortoResult<bool> {
+    let statuses: Vec<String> = statuses.iter().map(|s| s.to_string()).collect();
+    let qry = query!(
+        r#"
+        UPDATE jobs
+        SET status = $1,
+            attempts = $2,
+            run_at = $3,
+            locked_at = $4,
+            finished_at = $5,
+            last_error = $6
+        WHERE id = $7 AND status = ANY($8)
+        "#,
+        job.status,
+        job.attempts,
+        job.run_at,
+        job.locked_at,
+        job.finished_at,
+        job.last_error,
+        job.id,
+        &statuses
+    );
+
+    let result = run_qry!(qry, execute, pool, tx);
+
+    VortoResult::Ok(result.rows_affected() == 1)
+}
+
+// Keeps the lock of a running job fresh, so it is not taken as abandoned
+pub async fn heartbeat(id: i32, now: NaiveDateTime, pool: &PgPool) -> VortoResult<()> {
+    query!(
+        "UPDATE jobs SET locked_at = $1 WHERE id = $2 AND status = $3",
+        now,
+        id,
+        JobStatus::Running.to_string()
+    )
+    .execute(pool)
+    .await?;
+
+    VortoResult::Ok(())
+}
+
+pub async fn get_by_id(id: i32, pool: &PgPool) -> VortoResult<Job> {
+    let job_opt = query_as!(Job, "SELECT * FROM jobs WHERE id = $1", id)
+        .fetch_optional(pool)
+        .await?;
+
+    if let Some(job) = job_opt {
+        VortoResult::Ok(job)
+    } else {
+        job_not_found()
+    }
+}
+
+// Due pending job or a running one whose lock went stale, other workers skip the locked row.
+// Jobs of a bulk load that runs as many jobs as its concurrency allows wait
+pub async fn lock_next(
+    now: NaiveDateTime,
+    stale_before: NaiveDateTime,
+    tx: &mut Transaction<'_, Postgres>,
+) -> VortoResult<Option<Job>> {
+    let job = query_as!(
+        Job,
+        r#"
+        SELECT *
+        FROM jobs
+        WHERE (status = $1 AND run_at <= $2 AND (
+                bulk_load_id IS NULL
+                OR (SELECT count(*) FROM jobs r WHERE r.bulk_load_id = jobs.bulk_load_id AND r.status = $3)
+                    < (SELECT b.concurrency FROM bulk_loads b WHERE b.id = jobs.bulk_load_id)
+            ))
+            OR (status = $3 AND locked_at < $4)
+        ORDER BY run_at, id
+        LIMIT 1
+        FOR UPDATE SKIP LOCKED
+        "#,
+        JobStatus::Pending.to_string(),
+        now,
+        JobStatus::Running.to_string(),
+        stale_before
+    )
+    .fetch_optional(tx)
+    .await?;
+
+    VortoResult::Ok(job)
+}
+
+pub async fn count_running_by_bulk_load_id(
+    bulk_load_id: i32,
+    tx: &mut Transaction<'_, Postgres>,
+) -> VortoResult<i64> {
+    let count = query!(
+        r#"SELECT count(*) AS "count!" FROM jobs WHERE bulk_load_id = $1 AND status = $2"#,
+        bulk_load_id,
+        JobStatus::Running.to_string()
+    )
+    .map(|r| r.count)
+    .fetch_one(tx)
+    .await?;
+
+    VortoResult::Ok(count)
+}
+
+// Running ones are let to finish
+pub async fn cancel_pending_by_bulk_load_id(
+    bulk_load_id: i32,
+    now: NaiveDateTime,
+    tx: &mut Transaction<'_, Postgres>,
+) -> VortoResult<()> {
+    query!(
+        r#"
+        UPDATE jobs
+        SET status = $1, finished_at = $2
+        WHERE bulk_load_id = $3 AND status = $4
+        "#,
+        JobStatus::Cancelled.to_string(),
+        now,
+        bulk_load_id,
+        JobStatus::Pending.to_string()
+    )
+    .execute(tx)
+    .await?;
+
+    VortoResult::Ok(())
+}
+
+pub async fn search(
+    statuses: &Vec<JobStatus>,
+    kinds: &Vec<JobKind>,
+    skip: i64,
+    take: i64,
+    pool: &PgPool,
+) -> VortoResult<Vec<Job>> {
+    let mut qb = QryBuilder::new("SELECT * FROM jobs WHERE true");
+    if !statuses.is_empty() {
+        qb.push(" AND ").push_in(
+            "status",
+            statuses.iter().map(|s| s.to_string()).collect::<Vec<_>>(),
+        );
+    }
+    if !kinds.is_empty() {
+        qb.push(" AND ").push_in(
+            "kind",
+            kinds.iter().map(|k| k.to_string()).collect::<Vec<_>>(),
+        );
+    }
+    qb.push(" ORDER BY id DESC OFFSET ")
+        .push_bind(skip)
+        .push(" LIMIT ")
+        .push_bind(take);
+
+    let jobs = qb.build_as::<Job>().fetch_all(pool).await?;
+
+    VortoResult::Ok(jobs)
+}
+
+pub async fn insert_error(
+    job_error: &JobError,
+    pool: &PgPool,
+    tx: Option<&mut Transaction<'_, Postgres>>,
+) -> VortoResult<()> {
+    let qry = query!(
+        r#"
+        INSERT INTO job_errors
+            (job_id, attempt, error, created_at)
+        VALUES ($1, $2, $3, $4)
+        "#,
+        job_error.job_id,
+        job_error.attempt,
+        job_error.error,
+        job_error.created_at
+    );
+
+    run_qry!(qry, execute, pool, tx);
+
+    VortoResult::Ok(())
+}
+
+pub async fn get_errors_by_job_ids(
+    job_ids: &Vec<i32>,
+    pool: &PgPool,
+) -> VortoResult<Vec<JobError>> {
+    if job_ids.is_empty() {
+        return VortoResult::Ok(vec![]);
+    }
+
+    VortoResult::Ok(
+        QryBuilder::new("SELECT * FROM job_errors WHERE ")
+            .push_in("job_id", job_ids.clone())
+            .push(" ORDER BY job_id, id")
+            .build_as::<JobError>()
+            .fetch_all(pool)
+            .await?,
+    )
+}
@@ -1,10 +1,17 @@
 #[macro_use]
 pub mod common;
 
+pub mod bulk_load;
 pub mod game;
+pub mod job;
+pub mod raw_page;
+pub mod saved_search;
 pub mod team;
 pub mod team_result;
 pub mod voc;
 pub mod word;
 pub mod word_definition;
+pub mod word_definition_example;
+pub mod word_relation;
 pub mod word_result;
+pub mod word_revision;
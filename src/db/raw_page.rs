@@ -0,0 +1,46 @@
+use sqlx::{query, PgPool, Postgres, Transaction};
+
+use crate::{db::common::*, domain::raw_page::RawPage, error::VortoResult};
+
+// Newer fetch replaces the cached page of the same source
+pub async fn upsert(
+    raw_page: &RawPage,
+    pool: &PgPool,
+    tx: Option<&mut Transaction<'_, Postgres>>,
+) -> VortoResult<()> {
+    let qry = query!(
+        r#"
+        INSERT INTO raw_pages
+            (word_id, source, content, content_hash, fetched_at)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (word_id, source) DO UPDATE
+        SET content = EXCLUDED.content,
+            content_hash = EXCLUDED.content_hash,
+            fetched_at = EXCLUDED.fetched_at
+        "#,
+        raw_page.word_id,
+        raw_page.source,
+        raw_page.content,
+        raw_page.content_hash,
+        raw_page.fetched_at
+    );
+
+    run_qry!(qry, execute, pool, tx);
+
+    VortoResult::Ok(())
+}
+
+pub async fn get_by_word_ids(word_ids: &Vec<i32>, pool: &PgPool) -> VortoResult<Vec<RawPage>> {
+    if word_ids.is_empty() {
+        return VortoResult::Ok(vec![]);
+    }
+
+    VortoResult::Ok(
+        QryBuilder::new("SELECT * FROM raw_pages WHERE ")
+            .push_in("word_id", word_ids.clone())
+            .push(" ORDER BY word_id, fetched_at DESC, id DESC")
+            .build_as::<RawPage>()
+            .fetch_all(pool)
+            .await?,
+    )
+}
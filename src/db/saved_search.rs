@@ -0,0 +1,82 @@
+use sqlx::{query, query_as, PgPool, Postgres, Transaction};
+
+use crate::{
+    domain::saved_search::SavedSearch,
+    error::{VortoError, VortoErrorCode, VortoResult},
+};
+
+fn saved_search_not_found<T>() -> VortoResult<T> {
+    VortoResult::Err(VortoError::new(
+        VortoErrorCode::NotFound,
+        "Saved search not found".to_owned(),
+    ))
+}
+
+// A search saved again under the same name replaces the old one
+pub async fn upsert(
+    saved_search: &SavedSearch,
+    pool: &PgPool,
+    tx: Option<&mut Transaction<'_, Postgres>>,
+) -> VortoResult<i32> {
+    let qry = query!(
+        r#"
+        INSERT INTO saved_searches
+            (user_id, name, filter, field_orders, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        ON CONFLICT (user_id, name) DO UPDATE
+        SET filter = EXCLUDED.filter,
+            field_orders = EXCLUDED.field_orders,
+            updated_at = EXCLUDED.updated_at
+        RETURNING id
+        "#,
+        saved_search.user_id,
+        saved_search.name,
+        saved_search.filter,
+        saved_search.field_orders,
+        saved_search.created_at,
+        saved_search.updated_at
+    )
+    .map(|r| r.id);
+
+    VortoResult::Ok(run_qry!(qry, fetch_one, pool, tx))
+}
+
+pub async fn get_by_id(id: i32, pool: &PgPool) -> VortoResult<SavedSearch> {
+    let saved_search_opt = query_as!(
+        SavedSearch,
+        "SELECT * FROM saved_searches WHERE id = $1",
+        id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    if let Some(saved_search) = saved_search_opt {
+        VortoResult::Ok(saved_search)
+    } else {
+        saved_search_not_found()
+    }
+}
+
+pub async fn get_by_user_id(user_id: i32, pool: &PgPool) -> VortoResult<Vec<SavedSearch>> {
+    let saved_searches = query_as!(
+        SavedSearch,
+        "SELECT * FROM saved_searches WHERE user_id = $1 ORDER BY name",
+        user_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    VortoResult::Ok(saved_searches)
+}
+
+pub async fn delete(
+    id: i32,
+    pool: &PgPool,
+    tx: Option<&mut Transaction<'_, Postgres>>,
+) -> VortoResult<()> {
+    let qry = query!("DELETE FROM saved_searches WHERE id = $1", id);
+
+    run_qry!(qry, execute, pool, tx);
+
+    VortoResult::Ok(())
+}
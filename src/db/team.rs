@@ -1,6 +1,6 @@
-use sqlx::{PgPool, query, query_as};
+use sqlx::{PgPool, query};
 
-use crate::{domain::team::Team, error::VortoResult};
+use crate::{db::common::QryBuilder, domain::team::Team, error::VortoResult};
 
 pub async fn insert(name: &str, pool: &PgPool) -> VortoResult<()> {
     query!("INSERT INTO teams (name) VALUES ($1)", name).execute(pool).await?;
@@ -8,27 +8,21 @@ pub async fn insert(name: &str, pool: &PgPool) -> VortoResult<()> {
 }
 
 pub async fn get_by_ids_ordered(ids: &Vec<i32>, pool: &PgPool) -> VortoResult<Vec<Team>> {
-    let ordered_ids_str_qry = 
-        ids
-        .iter()
-        .enumerate()
-        .map(|(order, id)| format!("({}, {})", order, id))
-        .collect::<Vec<_>>()
-        .join(",");
-
-    let teams = query_as::<_, Team>(
-        &format!(
-            r#"
-            SELECT * 
-            FROM teams t
-            JOIN (VALUES {}) s("order", team_id) ON team_id = t.id
-            ORDER BY "order" ASC
-            "#,
-            ordered_ids_str_qry
-        )
+    let teams = QryBuilder::new(
+        r#"
+        SELECT t.*
+        FROM teams t
+        JOIN unnest("#,
+    )
+    .push_bind(ids.clone())
+    .push(
+        r#") WITH ORDINALITY s(team_id, "order") ON team_id = t.id
+        ORDER BY "order" ASC
+        "#,
     )
+    .build_as::<Team>()
     .fetch_all(pool)
     .await?;
 
     VortoResult::Ok(teams)
-}
\ No newline at end of file
+}
@@ -1,20 +1,33 @@
 use crate::domain::voc::Voc;
 use crate::{db::common::*, error::VortoResult};
-use sqlx::{PgPool, query_as};
+use sqlx::PgPool;
 
 
 pub async fn get_by_shorts(shorts: &Vec<String>, pool: &PgPool) -> VortoResult<Vec<Voc>> {
+    if shorts.is_empty() {
+        return VortoResult::Ok(vec![]);
+    }
+
     VortoResult::Ok(
-        query_as::<_, Voc>(&format!(
-            r#"
-            SELECT * 
-            FROM vocs
-            WHERE {}
-            "#, 
-            in_qry("short", &shorts))
-        )  
-        .fetch_all(pool)
-        .await?,
+        QryBuilder::new("SELECT * FROM vocs WHERE ")
+            .push_in("short", shorts.clone())
+            .build_as::<Voc>()
+            .fetch_all(pool)
+            .await?,
     )
 }
 
+
+pub async fn get_by_ids(ids: &Vec<i32>, pool: &PgPool) -> VortoResult<Vec<Voc>> {
+    if ids.is_empty() {
+        return VortoResult::Ok(vec![]);
+    }
+
+    VortoResult::Ok(
+        QryBuilder::new("SELECT * FROM vocs WHERE ")
+            .push_in("id", ids.clone())
+            .build_as::<Voc>()
+            .fetch_all(pool)
+            .await?,
+    )
+}
@@ -1,11 +1,18 @@
+use chrono::NaiveDateTime;
 use sqlx::{query, query_as, PgPool, Postgres, Transaction};
 
 use crate::{
-    domain::word::Word,
-    error::VortoResult,
+    db::common::*,
+    domain::{
+        enums::{Gender, PartOfSpeech, WordLoadStatus, WordStatus},
+        word::Word,
+    },
+    error::{VortoError, VortoErrorCode, VortoResult},
     responses::{DifficultyStats, LoadStatusStats, StatusStats, WordStats},
 };
 
+const BODY_INDEX: &str = "words_body_index";
+
 pub async fn get_by_id(id: i32, pool: &PgPool) -> VortoResult<Word> {
     let word = query_as!(Word, 
         r#"
@@ -17,6 +24,198 @@ pub async fn get_by_id(id: i32, pool: &PgPool) -> VortoResult<Word> {
     VortoResult::Ok(word)
 }
 
+// `normalized_body` is in the form of `domain::word::normalize_body`
+pub async fn get_by_normalized_body(
+    normalized_body: &str,
+    pool: &PgPool,
+) -> VortoResult<Option<Word>> {
+    let word = query_as!(
+        Word,
+        r#"
+        SELECT * FROM words WHERE lower(body) = $1
+        "#,
+        normalized_body
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    VortoResult::Ok(word)
+}
+
+// Rows stay locked until the transaction ends
+pub async fn lock_by_ids(
+    ids: &Vec<i32>,
+    tx: &mut Transaction<'_, Postgres>,
+) -> VortoResult<Vec<Word>> {
+    if ids.is_empty() {
+        return VortoResult::Ok(vec![]);
+    }
+
+    let words = QryBuilder::new("SELECT * FROM words WHERE ")
+        .push_in("id", ids.clone())
+        .push(" ORDER BY id FOR UPDATE")
+        .build_as::<Word>()
+        .fetch_all(tx)
+        .await?;
+
+    VortoResult::Ok(words)
+}
+
+pub async fn get_bodies(pool: &PgPool) -> VortoResult<Vec<String>> {
+    let bodies = query!("SELECT body FROM words")
+        .map(|r| r.body)
+        .fetch_all(pool)
+        .await?;
+
+    VortoResult::Ok(bodies)
+}
+
+pub async fn get_all(pool: &PgPool) -> VortoResult<Vec<Word>> {
+    let words = query_as!(Word, 
+        r#"
+        SELECT * FROM words
+        "#)
+        .fetch_all(pool)
+        .await?;
+
+    VortoResult::Ok(words)
+}
+
+// Random active words for a game, empty filters match everything
+pub async fn get_random_active(
+    count: i32,
+    difficulties: &Vec<i32>,
+    parts_of_speech: &Vec<PartOfSpeech>,
+    genders: &Vec<Gender>,
+    pool: &PgPool,
+) -> VortoResult<Vec<Word>> {
+    let mut qb = QryBuilder::new("SELECT * FROM words WHERE status = ");
+    qb.push_bind(WordStatus::Active.to_string());
+    if !difficulties.is_empty() {
+        qb.push(" AND ").push_in("difficulty", difficulties.clone());
+    }
+    if !parts_of_speech.is_empty() {
+        qb.push(" AND ").push_in(
+            "part_of_speech",
+            parts_of_speech
+                .iter()
+                .map(|p| p.to_string())
+                .collect::<Vec<_>>(),
+        );
+    }
+    if !genders.is_empty() {
+        qb.push(" AND ").push_in(
+            "gender",
+            genders.iter().map(|g| g.to_string()).collect::<Vec<_>>(),
+        );
+    }
+    qb.push(" ORDER BY random() LIMIT ").push_bind(count as i64);
+
+    let words = qb.build_as::<Word>().fetch_all(pool).await?;
+
+    VortoResult::Ok(words)
+}
+
+// Loaded words not synced since `synced_before`, the oldest first, archived ones are left alone
+pub async fn get_to_sync(
+    synced_before: NaiveDateTime,
+    limit: i64,
+    pool: &PgPool,
+) -> VortoResult<Vec<Word>> {
+    let words = query_as!(
+        Word,
+        r#"
+        SELECT *
+        FROM words
+        WHERE load_status = $1
+            AND status <> $2
+            AND (synced_at IS NULL OR synced_at < $3)
+        ORDER BY synced_at NULLS FIRST, id
+        LIMIT $4
+        "#,
+        WordLoadStatus::Loaded.to_string(),
+        WordStatus::Archived.to_string(),
+        synced_before,
+        limit
+    )
+    .fetch_all(pool)
+    .await?;
+
+    VortoResult::Ok(words)
+}
+
+// Doesn't touch the timestamp, nothing an editor sees has changed
+pub async fn set_synced_at(
+    id: i32,
+    synced_at: NaiveDateTime,
+    pool: &PgPool,
+    tx: Option<&mut Transaction<'_, Postgres>>,
+) -> VortoResult<()> {
+    run_qry!(
+        query!("UPDATE words SET synced_at = $1 WHERE id = $2", synced_at, id),
+        execute,
+        pool,
+        tx
+    );
+    VortoResult::Ok(())
+}
+
+// Timestamp is kept, an editor's open form of the word stays valid
+pub async fn set_has_upstream_changes(
+    id: i32,
+    pool: &PgPool,
+    tx: Option<&mut Transaction<'_, Postgres>>,
+) -> VortoResult<()> {
+    run_qry!(
+        query!("UPDATE words SET has_upstream_changes = TRUE WHERE id = $1", id),
+        execute,
+        pool,
+        tx
+    );
+    VortoResult::Ok(())
+}
+
+pub async fn insert(
+    word: &Word,
+    pool: &PgPool,
+    tx: Option<&mut Transaction<'_, Postgres>>,
+) -> VortoResult<i32> {
+    let qry = query!(
+        r#"
+        INSERT INTO words
+            (body, status, is_edited_after_load, load_status, difficulty, timestamp, part_of_speech, gender, tags)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        RETURNING id
+        "#,
+        word.body,
+        word.status,
+        word.is_edited_after_load,
+        word.load_status,
+        word.difficulty,
+        word.timestamp,
+        word.part_of_speech,
+        word.gender,
+        &word.tags
+    )
+    .map(|r| r.id);
+    let result = if let Some(t) = tx {
+        qry.fetch_one(t).await
+    } else {
+        qry.fetch_one(pool).await
+    };
+
+    // A word of the same body inserted meanwhile
+    match result {
+        Err(sqlx::Error::Database(e)) if e.constraint() == Some(BODY_INDEX) => {
+            VortoResult::Err(VortoError::new(
+                VortoErrorCode::Validation,
+                format!("Word '{}' already exists", word.body),
+            ))
+        }
+        result => VortoResult::Ok(result?),
+    }
+}
+
 pub async fn update(
     word: &Word,
     pool: &PgPool,
@@ -30,8 +229,19 @@ pub async fn update(
             is_edited_after_load = $3,
             load_status = $4,
             difficulty = $5,
-            timestamp = $6
-        WHERE id = $7
+            timestamp = $6,
+            load_fail_reason = $7,
+            part_of_speech = $8,
+            gender = $9,
+            syllables = $10,
+            load_fail_kind = $11,
+            load_fail_http_status = $12,
+            load_fail_stage = $13,
+            load_fail_attempts = $14,
+            synced_at = $15,
+            has_upstream_changes = $16,
+            tags = $17
+        WHERE id = $18
         "#,
         word.body,
         word.status,
@@ -39,6 +249,17 @@ pub async fn update(
         word.load_status,
         word.difficulty,
         word.timestamp,
+        word.load_fail_reason,
+        word.part_of_speech,
+        word.gender,
+        word.syllables,
+        word.load_fail_kind,
+        word.load_fail_http_status,
+        word.load_fail_stage,
+        word.load_fail_attempts,
+        word.synced_at,
+        word.has_upstream_changes,
+        &word.tags,
         word.id
     );
 
@@ -53,6 +274,7 @@ struct WordStatsQry {
     pub active: i64,
     pub not_active: i64,
     pub draft: i64,
+    pub archived: i64,
 
     // load status
     pub loaded: i64,
@@ -76,6 +298,7 @@ pub async fn word_stats(pool: &PgPool) -> VortoResult<WordStats> {
         SUM(case w.status when 'active' then 1 else 0 end) as "active!",	
         SUM(case w.status when 'not_active' then 1 else 0 end) as "not_active!",
         SUM(case w.status when 'draft' then 1 else 0 end) as "draft!",	
+        SUM(case w.status when 'archived' then 1 else 0 end) as "archived!",
         SUM(case w.load_status when 'loaded' then 1 else 0 end) as "loaded!",	
         SUM(case w.load_status when 'loaded_with_fail' then 1 else 0 end) as "loaded_with_fail!",
         SUM(case w.load_status when 'not_loaded' then 1 else 0 end) as "not_loaded!",
@@ -93,6 +316,7 @@ pub async fn word_stats(pool: &PgPool) -> VortoResult<WordStats> {
             active: word_stats_qry.active,
             draft: word_stats_qry.draft,
             not_active: word_stats_qry.not_active,
+            archived: word_stats_qry.archived,
         },
         load_status: LoadStatusStats {
             loaded: word_stats_qry.loaded,
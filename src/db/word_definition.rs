@@ -23,12 +23,12 @@ fn word_definition_insert(
             let first_voc = vocs
                 .iter()
                 .next()
-                .map(|v| v.clone())
-                .unwrap_or(String::new());
+                .cloned()
+                .unwrap_or_default();
             let voc_id = short_id_map
                 .get(&first_voc)
                 .map(|x| x.to_string())
-                .unwrap_or(String::from("null"));
+                .unwrap_or_else(|| String::from("null"));
             format!(
                 "('{}', '{}', {}, {}, {})",
                 def,
@@ -42,6 +42,64 @@ fn word_definition_insert(
         .join(",")
 }
 
+// Examples are not loaded, see word_definition_example
+pub async fn get_by_word_id(word_id: i32, pool: &PgPool) -> VortoResult<Vec<WordDefinition>> {
+    let word_definitions = query!(
+        r#"
+        SELECT *
+        FROM word_definitions
+        WHERE word_id = $1
+        ORDER BY "order"
+        "#,
+        word_id
+    )
+    .map(|r| WordDefinition {
+        id: r.id,
+        definition: r.definition,
+        status: r.status,
+        order: r.order,
+        word_id: r.word_id,
+        voc_id: r.voc_id,
+        source: r.source,
+        examples: vec![],
+        is_vanished: r.is_vanished,
+    })
+    .fetch_all(pool)
+    .await?;
+
+    VortoResult::Ok(word_definitions)
+}
+
+// Examples are not loaded either
+pub async fn get_by_word_ids(
+    word_ids: &Vec<i32>,
+    pool: &PgPool,
+    tx: Option<&mut Transaction<'_, Postgres>>,
+) -> VortoResult<Vec<WordDefinition>> {
+    let qry = query!(
+        r#"
+        SELECT *
+        FROM word_definitions
+        WHERE word_id = ANY($1)
+        ORDER BY word_id, "order"
+        "#,
+        word_ids
+    )
+    .map(|r| WordDefinition {
+        id: r.id,
+        definition: r.definition,
+        status: r.status,
+        order: r.order,
+        word_id: r.word_id,
+        voc_id: r.voc_id,
+        source: r.source,
+        examples: vec![],
+        is_vanished: r.is_vanished,
+    });
+
+    VortoResult::Ok(run_qry!(qry, fetch_all, pool, tx))
+}
+
 pub async fn delete_by_word_id(
     word_id: i32,
     pool: &PgPool,
@@ -64,22 +122,23 @@ pub async fn insert(
     let qry = query!(
         r#"
         INSERT INTO word_definitions
-            (definition, status, "order", word_id, voc_id)
-        VALUES ($1, $2, $3, $4, $5)
+            (definition, status, "order", word_id, voc_id, source, is_vanished)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
         RETURNING id   
         "#,
         word_definition.definition,
         word_definition.status,
         word_definition.order,
         word_definition.word_id,
-        word_definition.voc_id
+        word_definition.voc_id,
+        word_definition.source,
+        word_definition.is_vanished
     )
     .map(|r| r.id);
 
     VortoResult::Ok(run_qry!(qry, fetch_one, pool, tx))
 }
 
-#[allow(dead_code)]
 pub async fn update(
     word_definition: &WordDefinition,
     pool: &PgPool,
@@ -92,14 +151,18 @@ pub async fn update(
             status = $2, 
             "order" = $3,
             word_id = $4,
-            voc_id = $5
-        WHERE id = $6
+            voc_id = $5,
+            source = $6,
+            is_vanished = $7
+        WHERE id = $8
         "#,
         word_definition.definition,
         word_definition.status,
         word_definition.order,
         word_definition.word_id,
         word_definition.voc_id,
+        word_definition.source,
+        word_definition.is_vanished,
         word_definition.id
     );
 
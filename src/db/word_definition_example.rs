@@ -0,0 +1,64 @@
+use sqlx::{query, PgPool, Postgres, Transaction};
+
+use crate::{db::common::*, error::VortoResult};
+
+pub async fn get_by_word_definition_ids(
+    word_definition_ids: &Vec<i32>,
+    pool: &PgPool,
+    tx: Option<&mut Transaction<'_, Postgres>>,
+) -> VortoResult<Vec<(i32, String)>> {
+    if word_definition_ids.is_empty() {
+        return VortoResult::Ok(vec![]);
+    }
+
+    let mut qb =
+        QryBuilder::new("SELECT word_definition_id, example FROM word_definition_examples WHERE ");
+    qb.push_in("word_definition_id", word_definition_ids.clone())
+        .push(r#" ORDER BY word_definition_id, "order""#);
+    let qry = qb.build_as::<(i32, String)>();
+
+    VortoResult::Ok(run_qry!(qry, fetch_all, pool, tx))
+}
+
+pub async fn delete_by_word_id(
+    word_id: i32,
+    pool: &PgPool,
+    tx: Option<&mut Transaction<'_, Postgres>>,
+) -> VortoResult<()> {
+    run_qry!(
+        query!(
+            r#"
+            DELETE FROM word_definition_examples
+            WHERE word_definition_id IN (SELECT id FROM word_definitions WHERE word_id = $1)
+            "#,
+            word_id
+        ),
+        execute,
+        pool,
+        tx
+    );
+    VortoResult::Ok(())
+}
+
+pub async fn insert(
+    word_definition_id: i32,
+    example: &str,
+    order: i32,
+    pool: &PgPool,
+    tx: Option<&mut Transaction<'_, Postgres>>,
+) -> VortoResult<()> {
+    let qry = query!(
+        r#"
+        INSERT INTO word_definition_examples
+            (example, "order", word_definition_id)
+        VALUES ($1, $2, $3)
+        "#,
+        example,
+        order,
+        word_definition_id
+    );
+
+    run_qry!(qry, execute, pool, tx);
+
+    VortoResult::Ok(())
+}
@@ -0,0 +1,54 @@
+use sqlx::{query, PgPool, Postgres, Transaction};
+
+use crate::{db::common::*, domain::word_relation::WordRelation, error::VortoResult};
+
+pub async fn get_by_word_ids(word_ids: &Vec<i32>, pool: &PgPool) -> VortoResult<Vec<WordRelation>> {
+    if word_ids.is_empty() {
+        return VortoResult::Ok(vec![]);
+    }
+
+    VortoResult::Ok(
+        QryBuilder::new("SELECT * FROM word_relations WHERE ")
+            .push_in("word_id", word_ids.clone())
+            .push(r#" ORDER BY word_id, "order""#)
+            .build_as::<WordRelation>()
+            .fetch_all(pool)
+            .await?,
+    )
+}
+
+pub async fn delete_by_word_id(
+    word_id: i32,
+    pool: &PgPool,
+    tx: Option<&mut Transaction<'_, Postgres>>,
+) -> VortoResult<()> {
+    run_qry!(
+        query!("DELETE FROM word_relations WHERE word_id = $1", word_id),
+        execute,
+        pool,
+        tx
+    );
+    VortoResult::Ok(())
+}
+
+pub async fn insert(
+    word_relation: &WordRelation,
+    pool: &PgPool,
+    tx: Option<&mut Transaction<'_, Postgres>>,
+) -> VortoResult<()> {
+    let qry = query!(
+        r#"
+        INSERT INTO word_relations
+            (kind, body, "order", word_id)
+        VALUES ($1, $2, $3, $4)
+        "#,
+        word_relation.kind,
+        word_relation.body,
+        word_relation.order,
+        word_relation.word_id
+    );
+
+    run_qry!(qry, execute, pool, tx);
+
+    VortoResult::Ok(())
+}
@@ -0,0 +1,52 @@
+use sqlx::{query, query_as, PgPool, Postgres, Transaction};
+
+use crate::{domain::word_revision::WordRevision, error::VortoResult};
+
+pub async fn insert(
+    word_revision: &WordRevision,
+    pool: &PgPool,
+    tx: Option<&mut Transaction<'_, Postgres>>,
+) -> VortoResult<i32> {
+    let qry = query!(
+        r#"
+        INSERT INTO word_revisions
+            (word_id, user_id, action, before, after, created_at)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        RETURNING id
+        "#,
+        word_revision.word_id,
+        word_revision.user_id,
+        word_revision.action,
+        word_revision.before,
+        word_revision.after,
+        word_revision.created_at
+    )
+    .map(|r| r.id);
+
+    VortoResult::Ok(run_qry!(qry, fetch_one, pool, tx))
+}
+
+pub async fn get_by_id(id: i32, pool: &PgPool) -> VortoResult<WordRevision> {
+    let word_revision = query_as!(
+        WordRevision,
+        "SELECT * FROM word_revisions WHERE id = $1",
+        id
+    )
+    .fetch_one(pool)
+    .await?;
+
+    VortoResult::Ok(word_revision)
+}
+
+// The latest first
+pub async fn get_by_word_id(word_id: i32, pool: &PgPool) -> VortoResult<Vec<WordRevision>> {
+    let word_revisions = query_as!(
+        WordRevision,
+        "SELECT * FROM word_revisions WHERE word_id = $1 ORDER BY id DESC",
+        word_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    VortoResult::Ok(word_revisions)
+}
@@ -0,0 +1,149 @@
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+
+use crate::error::{VortoError, VortoErrorCode, VortoResult};
+
+use super::{
+    common::validate_fn,
+    enums::{BulkLoadState, DefinitionSourceKind},
+};
+
+const LAST_ERRORS_SIZE: usize = 20;
+const ERROR_MAX_LEN: usize = 2000;
+
+#[derive(Clone, Debug, sqlx::FromRow)]
+pub struct BulkLoad {
+    pub id: i32,
+    pub state: String,
+    pub source: String,
+    pub total: i32,
+    pub processed: i32,
+    pub loaded: i32,
+    pub failed: i32,
+    pub last_errors: Vec<String>,
+    pub requests_per_second: i32,
+    pub concurrency: i32,
+    pub started_at: NaiveDateTime,
+    pub finished_at: Option<NaiveDateTime>,
+}
+
+fn validate_requests_per_second(requests_per_second: u32) -> VortoResult<()> {
+    validate_fn(
+        || !(1..=50).contains(&requests_per_second),
+        VortoError::new(
+            VortoErrorCode::Validation,
+            "Requests per second valid range 1-50".to_owned(),
+        ),
+    )
+}
+
+fn validate_concurrency(concurrency: u32) -> VortoResult<()> {
+    validate_fn(
+        || !(1..=20).contains(&concurrency),
+        VortoError::new(
+            VortoErrorCode::Validation,
+            "Concurrency valid range 1-20".to_owned(),
+        ),
+    )
+}
+
+fn is_running(bulk_load: &BulkLoad) -> bool {
+    bulk_load.state == BulkLoadState::Running.to_string()
+}
+
+pub fn validate_not_running(bulk_load: Option<&BulkLoad>) -> VortoResult<()> {
+    validate_fn(
+        || matches!(bulk_load, Some(b) if is_running(b)),
+        VortoError::new(
+            VortoErrorCode::Validation,
+            "Bulk load is already running".to_owned(),
+        ),
+    )
+}
+
+pub fn new(
+    source: &DefinitionSourceKind,
+    total: i32,
+    requests_per_second: u32,
+    concurrency: u32,
+    now: &DateTime<Utc>,
+) -> VortoResult<BulkLoad> {
+    validate_requests_per_second(requests_per_second)?;
+    validate_concurrency(concurrency)?;
+
+    VortoResult::Ok(BulkLoad {
+        id: -1,
+        state: BulkLoadState::Running.to_string(),
+        source: source.to_string(),
+        total,
+        processed: 0,
+        loaded: 0,
+        failed: 0,
+        last_errors: vec![],
+        requests_per_second: requests_per_second as i32,
+        concurrency: concurrency as i32,
+        started_at: now.naive_utc(),
+        finished_at: None,
+    })
+}
+
+// The rate limit, the job of the `index`-th word is not due before this
+pub fn run_at(bulk_load: &BulkLoad, index: usize) -> NaiveDateTime {
+    bulk_load.started_at
+        + Duration::milliseconds(index as i64 * 1000 / bulk_load.requests_per_second as i64)
+}
+
+// The concurrency limit, `running` is the number of its jobs being run
+pub fn has_free_slot(bulk_load: &BulkLoad, running: i64) -> bool {
+    running < bulk_load.concurrency as i64
+}
+
+// `result` is an error of the word: a parse failure, no definitions or a failed job.
+// The last result completes a running bulk load
+pub fn add_result(
+    bulk_load: &BulkLoad,
+    body: &str,
+    result: Result<(), String>,
+    now: &DateTime<Utc>,
+) -> BulkLoad {
+    let mut new_bulk_load = BulkLoad {
+        processed: bulk_load.processed + 1,
+        ..bulk_load.clone()
+    };
+    match result {
+        Ok(_) => new_bulk_load.loaded += 1,
+        Err(message) => {
+            new_bulk_load.failed += 1;
+            new_bulk_load.last_errors.push(
+                format!("{}: {}", body, message)
+                    .chars()
+                    .take(ERROR_MAX_LEN)
+                    .collect(),
+            );
+            if new_bulk_load.last_errors.len() > LAST_ERRORS_SIZE {
+                new_bulk_load.last_errors.remove(0);
+            }
+        }
+    }
+    if is_running(&new_bulk_load) && new_bulk_load.processed >= new_bulk_load.total {
+        new_bulk_load.state = BulkLoadState::Completed.to_string();
+        new_bulk_load.finished_at = Some(now.naive_utc());
+    }
+    new_bulk_load
+}
+
+// Words which are being loaded right now are finished, the rest are skipped
+pub fn cancel(bulk_load: &BulkLoad, now: &DateTime<Utc>) -> VortoResult<BulkLoad> {
+    validate_fn(
+        || !is_running(bulk_load),
+        VortoError::new(
+            VortoErrorCode::Validation,
+            "Bulk load is not running".to_owned(),
+        ),
+    )?;
+
+    VortoResult::Ok(BulkLoad {
+        state: BulkLoadState::Cancelled.to_string(),
+        finished_at: Some(now.naive_utc()),
+        ..bulk_load.clone()
+    })
+}
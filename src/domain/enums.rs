@@ -8,6 +8,8 @@ pub enum WordStatus {
     Active,
     NotActive,
     Draft,
+    // Deleted by an admin, kept for results of played games
+    Archived,
 }
 
 #[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Debug, Display, EnumString)]
@@ -33,4 +35,154 @@ pub enum WordDefinitionStatus {
 pub enum GameState {
     Active,
     Ended
-}
\ No newline at end of file
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Hash, Debug, Display, EnumString)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum DefinitionSourceKind {
+    Wiktionary,
+    EnWiktionary,
+    WiktionaryDump,
+    WiktionaryApi,
+    LocalFile,
+}
+
+impl Default for DefinitionSourceKind {
+    fn default() -> Self {
+        DefinitionSourceKind::Wiktionary
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Hash, Debug, Display, EnumString)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum WordRelationKind {
+    Synonym,
+    Antonym,
+    Related,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Hash, Debug, Display, EnumString)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum PartOfSpeech {
+    Noun,
+    Adjective,
+    Verb,
+    Participle,
+    Adverb,
+    Pronoun,
+    Numeral,
+    Preposition,
+    Conjunction,
+    Particle,
+    Interjection,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Hash, Debug, Display, EnumString)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum Gender {
+    Masculine,
+    Feminine,
+    Neuter,
+    Common,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Debug, Display, EnumString)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum BulkLoadState {
+    Running,
+    Completed,
+    Cancelled,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Debug, Display, EnumString)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum JobKind {
+    LoadDefinitions,
+    SyncDefinitions,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Debug, Display, EnumString)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum JobStatus {
+    Pending,
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Debug, Display, EnumString)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum LoadFailKind {
+    Unavailable,
+    HttpStatus,
+    PageNotFound,
+    Redirect,
+    MissingSection,
+    MissingNode,
+    InvalidContent,
+    NoDefinitions,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Debug, Display, EnumString)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum ImportFormat {
+    // Header row with `body` and optional `difficulty`, `status` and `tags` columns
+    Csv,
+    // A word per line
+    Txt,
+    // Array of objects with the same fields as the csv
+    Json,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Debug, Display, EnumString)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum ExportFormat {
+    // A row per definition, word columns are repeated
+    Csv,
+    // A word view per line
+    JsonLines,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Debug, Display, EnumString)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum WordRevisionAction {
+    Create,
+    Update,
+    BulkEdit,
+    Archive,
+    ResolveUpstreamChanges,
+    Revert,
+    Load,
+    Sync,
+    Reparse,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Debug, Display, EnumString)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum TextSearchMode {
+    // Start of the body
+    Prefix,
+    // Body with typos, by trigram similarity
+    Fuzzy,
+    // Words of the definitions, stemmed
+    FullText,
+}
+
+impl Default for TextSearchMode {
+    fn default() -> Self {
+        TextSearchMode::Prefix
+    }
+}
@@ -42,7 +42,7 @@ pub fn validate_team_count(teams: &Vec<Team>) -> VortoResult<()> {
 
 pub fn validate_round_time(round_time: i32) -> VortoResult<()> {
     validate_fn(
-        || round_time < 1 || round_time > 1000,
+        || !(1..=1000).contains(&round_time),
         VortoError::new(
             VortoErrorCode::Validation,
             "Round time valid range 1-1000".to_owned(),
@@ -50,9 +50,9 @@ pub fn validate_round_time(round_time: i32) -> VortoResult<()> {
     )
 }
 
-fn validate_word_count(word_count: i32) -> VortoResult<()> {
+pub fn validate_word_count(word_count: i32) -> VortoResult<()> {
     validate_fn(
-        || word_count < 1 || word_count > 500,
+        || !(1..=500).contains(&word_count),
         VortoError::new(
             VortoErrorCode::Validation,
             "Word count valid range 1-5000".to_owned(),
@@ -130,8 +130,7 @@ fn get_current_team_result(
     team_results_words
         .iter()
         .cycle()
-        .skip(game.turn as usize)
-        .next()
+        .nth(game.turn as usize)
         .unwrap()
         .0
         .clone()
@@ -0,0 +1,194 @@
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+
+use crate::error::{VortoError, VortoErrorCode, VortoResult};
+
+use super::{
+    common::validate_fn,
+    enums::{JobKind, JobStatus},
+};
+
+const ERROR_MAX_LEN: usize = 2000;
+const BACKOFF_BASE_SECONDS: i64 = 30;
+const BACKOFF_MAX_SECONDS: i64 = 60 * 60;
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct Job {
+    pub id: i32,
+    pub kind: String,
+    pub payload: String,
+    pub status: String,
+    pub attempts: i32,
+    pub max_attempts: i32,
+    pub run_at: NaiveDateTime,
+    pub locked_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub finished_at: Option<NaiveDateTime>,
+    pub last_error: Option<String>,
+    pub bulk_load_id: Option<i32>,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct JobError {
+    pub id: i32,
+    pub job_id: i32,
+    pub attempt: i32,
+    pub error: String,
+    pub created_at: NaiveDateTime,
+}
+
+fn validate_max_attempts(max_attempts: i32) -> VortoResult<()> {
+    validate_fn(
+        || !(1..=20).contains(&max_attempts),
+        VortoError::new(
+            VortoErrorCode::Validation,
+            "Max attempts valid range 1-20".to_owned(),
+        ),
+    )
+}
+
+fn validate_status(job: &Job, statuses: &[JobStatus], action: &str) -> VortoResult<()> {
+    validate_fn(
+        || !statuses.iter().any(|s| s.to_string() == job.status),
+        VortoError::new(
+            VortoErrorCode::Validation,
+            format!("Job with status '{}' can't be {}", job.status, action),
+        ),
+    )
+}
+
+fn truncate_error(error: &str) -> String {
+    error.chars().take(ERROR_MAX_LEN).collect()
+}
+
+// 30s, 1m, 2m, 4m... but not longer than an hour
+fn backoff(attempts: i32) -> Duration {
+    let exponent = (attempts - 1).max(0).min(16) as u32;
+    Duration::seconds((BACKOFF_BASE_SECONDS * 2i64.pow(exponent)).min(BACKOFF_MAX_SECONDS))
+}
+
+pub fn new(
+    kind: &JobKind,
+    payload: &str,
+    max_attempts: i32,
+    now: &DateTime<Utc>,
+) -> VortoResult<Job> {
+    validate_max_attempts(max_attempts)?;
+
+    VortoResult::Ok(Job {
+        id: -1,
+        kind: kind.to_string(),
+        payload: payload.to_owned(),
+        status: JobStatus::Pending.to_string(),
+        attempts: 0,
+        max_attempts,
+        run_at: now.naive_utc(),
+        locked_at: None,
+        created_at: now.naive_utc(),
+        finished_at: None,
+        last_error: None,
+        bulk_load_id: None,
+    })
+}
+
+// Jobs of a bulk load are spread over time by `run_at`
+pub fn for_bulk_load(job: &Job, bulk_load_id: i32, run_at: NaiveDateTime) -> Job {
+    Job {
+        run_at,
+        bulk_load_id: Some(bulk_load_id),
+        ..job.clone()
+    }
+}
+
+pub fn is_failed(job: &Job) -> bool {
+    job.status == JobStatus::Failed.to_string()
+}
+
+pub fn start(job: &Job, now: &DateTime<Utc>) -> Job {
+    Job {
+        status: JobStatus::Running.to_string(),
+        attempts: job.attempts + 1,
+        locked_at: Some(now.naive_utc()),
+        ..job.clone()
+    }
+}
+
+pub fn complete(job: &Job, now: &DateTime<Utc>) -> Job {
+    Job {
+        status: JobStatus::Completed.to_string(),
+        locked_at: None,
+        finished_at: Some(now.naive_utc()),
+        last_error: None,
+        ..job.clone()
+    }
+}
+
+// Goes back to the queue with a backoff until attempts are over
+pub fn fail(job: &Job, error: &str, now: &DateTime<Utc>) -> (Job, JobError) {
+    let error = truncate_error(error);
+    let new_job = if job.attempts < job.max_attempts {
+        Job {
+            status: JobStatus::Pending.to_string(),
+            run_at: (*now + backoff(job.attempts)).naive_utc(),
+            locked_at: None,
+            last_error: Some(error.clone()),
+            ..job.clone()
+        }
+    } else {
+        Job {
+            status: JobStatus::Failed.to_string(),
+            locked_at: None,
+            finished_at: Some(now.naive_utc()),
+            last_error: Some(error.clone()),
+            ..job.clone()
+        }
+    };
+    let job_error = JobError {
+        id: -1,
+        job_id: job.id,
+        attempt: job.attempts,
+        error,
+        created_at: now.naive_utc(),
+    };
+
+    (new_job, job_error)
+}
+
+// Running job whose worker has stopped, that attempt counts as a failed one
+pub fn abandon(job: &Job, now: &DateTime<Utc>) -> (Job, JobError) {
+    fail(job, "Worker stopped before the job finished", now)
+}
+
+pub const RETRYABLE_STATUSES: [JobStatus; 2] = [JobStatus::Failed, JobStatus::Cancelled];
+pub const CANCELLABLE_STATUSES: [JobStatus; 2] = [JobStatus::Pending, JobStatus::Running];
+
+// The result of a bulk load job is counted once, its words are loaded again by a new bulk load
+pub fn retry(job: &Job, now: &DateTime<Utc>) -> VortoResult<Job> {
+    validate_status(job, &RETRYABLE_STATUSES, "retried")?;
+    validate_fn(
+        || job.bulk_load_id.is_some(),
+        VortoError::new(
+            VortoErrorCode::Validation,
+            "Job of a bulk load can't be retried".to_owned(),
+        ),
+    )?;
+
+    VortoResult::Ok(Job {
+        status: JobStatus::Pending.to_string(),
+        attempts: 0,
+        run_at: now.naive_utc(),
+        finished_at: None,
+        ..job.clone()
+    })
+}
+
+// A running job is let to finish, its result is just not recorded
+pub fn cancel(job: &Job, now: &DateTime<Utc>) -> VortoResult<Job> {
+    validate_status(job, &CANCELLABLE_STATUSES, "cancelled")?;
+
+    VortoResult::Ok(Job {
+        status: JobStatus::Cancelled.to_string(),
+        locked_at: None,
+        finished_at: Some(now.naive_utc()),
+        ..job.clone()
+    })
+}
@@ -1,10 +1,16 @@
 pub mod word;
 pub mod common;
 pub mod word_definition;
+pub mod bulk_load;
+pub mod word_relation;
 pub mod voc;
 pub mod enums;
 pub mod user;
 pub mod game;
 pub mod team_result;
 pub mod word_result;
-pub mod team;
\ No newline at end of file
+pub mod team;
+pub mod job;
+pub mod raw_page;
+pub mod word_revision;
+pub mod saved_search;
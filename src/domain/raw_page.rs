@@ -0,0 +1,35 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
+use sha2::{Digest, Sha256};
+
+use super::enums::DefinitionSourceKind;
+
+// Page of a word as it was fetched from a source, the last one per source is kept
+#[derive(Clone, Debug, sqlx::FromRow)]
+pub struct RawPage {
+    pub id: i32,
+    pub word_id: i32,
+    pub source: String,
+    pub content: String,
+    pub content_hash: String,
+    pub fetched_at: NaiveDateTime,
+}
+
+pub fn content_hash(content: &str) -> String {
+    format!("{:x}", Sha256::digest(content.as_bytes()))
+}
+
+pub fn new(
+    word_id: i32,
+    source: &DefinitionSourceKind,
+    content: &str,
+    now: &DateTime<Utc>,
+) -> RawPage {
+    RawPage {
+        id: -1,
+        word_id,
+        source: source.to_string(),
+        content: content.to_owned(),
+        content_hash: content_hash(content),
+        fetched_at: now.naive_utc(),
+    }
+}
@@ -0,0 +1,56 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
+
+use crate::error::{VortoError, VortoErrorCode, VortoResult};
+
+use super::common::validate_fn;
+
+// Filter and orders are kept as JSON of the search request parts
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct SavedSearch {
+    pub id: i32,
+    pub user_id: i32,
+    pub name: String,
+    pub filter: String,
+    pub field_orders: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+fn validate_name(name: &str) -> VortoResult<()> {
+    validate_fn(
+        || name.is_empty() || name.chars().count() > 255,
+        VortoError::new(VortoErrorCode::Validation, "Name size 1-255".to_owned()),
+    )
+}
+
+pub fn new(
+    user_id: i32,
+    name: &str,
+    filter: &str,
+    field_orders: &str,
+    time: &DateTime<Utc>,
+) -> VortoResult<SavedSearch> {
+    let name = name.trim();
+    validate_name(name)?;
+
+    VortoResult::Ok(SavedSearch {
+        id: -1,
+        user_id,
+        name: name.to_owned(),
+        filter: filter.to_owned(),
+        field_orders: field_orders.to_owned(),
+        created_at: time.naive_utc(),
+        updated_at: time.naive_utc(),
+    })
+}
+
+// Searches of other admins are not visible
+pub fn check_owner(saved_search: &SavedSearch, user_id: i32) -> VortoResult<()> {
+    validate_fn(
+        || saved_search.user_id != user_id,
+        VortoError::new(
+            VortoErrorCode::NotFound,
+            "Saved search not found".to_owned(),
+        ),
+    )
+}
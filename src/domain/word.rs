@@ -1,21 +1,29 @@
 
 
+use std::collections::{HashMap, HashSet};
+
 use crate::{
     common::{reduce_results},
-    error::{VortoError, VortoErrorCode, VortoResult},
+    error::{ParseError, VortoError, VortoErrorCode, VortoResult},
 };
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use itertools::Itertools;
 use regex::Regex;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-use super::{voc::Voc, word_definition};
-use super::word_definition::WordDefinition;
+use super::{voc::Voc, word_definition, word_relation};
+use super::word_definition::{WordDefinition, WordDefinitionParams};
+use super::word_relation::WordRelation;
+use super::word_revision::WordSnapshot;
 use super::{
     common::validate_fn,
-    enums::{WordDefinitionStatus, WordLoadStatus, WordStatus},
+    enums::{
+        DefinitionSourceKind, Gender, PartOfSpeech, WordDefinitionStatus, WordLoadStatus,
+        WordRelationKind, WordStatus,
+    },
 };
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Clone, Debug, sqlx::FromRow)]
 pub struct Word {
     pub id: i32,
     pub body: String,
@@ -24,17 +32,110 @@ pub struct Word {
     pub load_status: String,
     pub difficulty: i32,
     pub timestamp: i64,
+    pub load_fail_reason: Option<String>,
+    pub part_of_speech: Option<String>,
+    pub gender: Option<String>,
+    pub syllables: Option<String>,
+    pub load_fail_kind: Option<String>,
+    pub load_fail_http_status: Option<i32>,
+    pub load_fail_stage: Option<String>,
+    // Failed loads in a row, a successful one resets it
+    pub load_fail_attempts: i32,
+    // Last load or scheduled re-fetch of the word
+    pub synced_at: Option<NaiveDateTime>,
+    pub has_upstream_changes: bool,
+    pub tags: Vec<String>,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct WordDefinitionDTO {
     pub definition: String,
     pub status: WordDefinitionStatus,
     pub voc_id: Option<i32>,
+    // Required even when null, definitions are rebuilt from the DTO and would lose it
+    #[serde(deserialize_with = "Option::deserialize")]
+    pub source: Option<DefinitionSourceKind>,
+    // Required too, an empty list is what removes the examples
+    pub examples: Vec<String>,
+    // Also required, a left out flag would bring back every vanished definition
+    pub is_vanished: bool,
+}
+
+// A row of an imported word list, missing fields are taken from the import
+#[derive(Deserialize, Clone, Debug)]
+pub struct ImportedWordDTO {
+    pub body: String,
+    #[serde(default)]
+    pub difficulty: Option<i32>,
+    #[serde(default)]
+    pub status: Option<WordStatus>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+// A change applied to every word of a bulk edit
+#[derive(Deserialize, Clone, Debug)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum BulkEditAction {
+    SetStatus { status: WordStatus },
+    SetDifficulty { difficulty: i32 },
+    AddTags { tags: Vec<String> },
+    RemoveTags { tags: Vec<String> },
+    ActivateDefinitions,
+}
+
+// What a definition source found on a page, vocs are label shorts as written there
+#[derive(Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct ParsedDefinition {
+    pub vocs: Vec<String>,
+    pub definition: String,
+    #[serde(default)]
+    pub examples: Vec<String>,
+}
+
+// `syllables` is the headword with stress marks and syllable breaks, e.g. "ко·то·фе́й"
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ParsedPage {
+    pub definitions: Vec<ParsedDefinition>,
+    pub relations: Vec<(WordRelationKind, String)>,
+    pub part_of_speech: Option<PartOfSpeech>,
+    pub gender: Option<Gender>,
+    pub syllables: Option<String>,
 }
 
+// A word with its definitions after a load, relations are None when they should be kept
+pub type LoadedWord = (Word, Vec<WordDefinition>, Option<Vec<WordRelation>>);
+
+const DEFAULT_DIFFICULTY: i32 = 1;
+
 lazy_static! {
     static ref BODY_REGEX: Regex = Regex::new(r".{1,255}").unwrap();
+    static ref WHITESPACE_REGEX: Regex = Regex::new(r"\s+").unwrap();
+}
+
+// Bodies are stored in this form, e.g. " Белый  медведь" is "Белый медведь"
+pub fn clean_body(body: &str) -> String {
+    WHITESPACE_REGEX.replace_all(body.trim(), " ").into_owned()
+}
+
+// Bodies are compared in this form, e.g. "Москва" and "москва" are the same word
+pub fn normalize_body(body: &str) -> String {
+    clean_body(body).to_lowercase()
+}
+
+pub fn normalize_tags(tags: &Vec<String>) -> Vec<String> {
+    tags.iter()
+        .map(|t| normalize_body(t))
+        .filter(|t| !t.is_empty())
+        .unique()
+        .collect()
+}
+
+fn validate_tags(tags: &Vec<String>) -> VortoResult<()> {
+    validate_fn(
+        || tags.iter().any(|t| t.chars().count() > 255),
+        VortoError::new(VortoErrorCode::Validation, "Tag size 1-255".to_owned()),
+    )
 }
 
 fn validate_body(body: &str) -> VortoResult<()> {
@@ -55,100 +156,574 @@ fn check_timestamp(word_timestamp: i64, new_timestamp: i64) -> VortoResult<()> {
     }
 }
 
+fn loaded_relations(
+    word: &Word,
+    relations: &Vec<(WordRelationKind, String)>,
+) -> VortoResult<Vec<WordRelation>> {
+    let mut seen = HashSet::new();
+
+    reduce_results(
+        &relations
+            .iter()
+            .filter(|(kind, body)| *body != word.body && seen.insert((*kind, body.clone())))
+            .enumerate()
+            .map(|(order, (kind, body))| word_relation::new(-1, kind, body, order as i32, word.id))
+            .collect::<Vec<_>>(),
+    )
+}
+
+fn loaded_definitions(
+    word: &Word,
+    page: &ParsedPage,
+    vocs: &HashMap<String, Voc>,
+    source: &DefinitionSourceKind,
+) -> VortoResult<Vec<WordDefinition>> {
+    reduce_results(
+        &page
+            .definitions
+            .iter()
+            .enumerate()
+            .map(|(order, parsed)| {
+                // A long quote is not a reason to fail the whole load
+                let examples = parsed
+                    .examples
+                    .iter()
+                    .filter(|e| e.len() <= word_definition::EXAMPLE_MAX_LEN)
+                    .cloned()
+                    .collect();
+
+                word_definition::new(
+                    -1,
+                    order as i32,
+                    word.id,
+                    &WordDefinitionParams {
+                        definition: &parsed.definition,
+                        status: &WordDefinitionStatus::NotActive,
+                        voc_id: parsed.vocs.first().and_then(|v| vocs.get(v)).map(|v| v.id),
+                        source: Some(source),
+                        examples: &examples,
+                        is_vanished: false,
+                    },
+                )
+            })
+            .collect::<Vec<_>>(),
+    )
+}
+
+// Definitions found again keep their status, voc and examples, new ones go after them
+// and the ones of this source which are not on the page anymore are flagged instead of
+// being deleted. Definitions added by editors or other sources are left as they are
+fn merge_definitions(
+    current_definitions: &Vec<WordDefinition>,
+    loaded_definitions: &Vec<WordDefinition>,
+    source: &DefinitionSourceKind,
+) -> Vec<WordDefinition> {
+    let source = Some(source.to_string());
+    let loaded: HashSet<String> = loaded_definitions
+        .iter()
+        .map(|wd| word_definition::normalize(&wd.definition))
+        .collect();
+    let mut seen: HashSet<String> = current_definitions
+        .iter()
+        .map(|wd| word_definition::normalize(&wd.definition))
+        .collect();
+
+    current_definitions
+        .iter()
+        .map(|wd| WordDefinition {
+            is_vanished: if wd.source == source {
+                !loaded.contains(&word_definition::normalize(&wd.definition))
+            } else {
+                wd.is_vanished
+            },
+            ..wd.clone()
+        })
+        .chain(
+            loaded_definitions
+                .iter()
+                .filter(|wd| seen.insert(word_definition::normalize(&wd.definition)))
+                .cloned(),
+        )
+        .enumerate()
+        .map(|(order, wd)| WordDefinition {
+            order: order as i32,
+            ..wd
+        })
+        .collect()
+}
+
+// Keeps what went wrong, so words failed for the same reason can be found and reloaded together
+pub fn fail_load(word: &Word, parse_error: &ParseError, time: &DateTime<Utc>) -> Word {
+    Word {
+        load_status: WordLoadStatus::LoadedWithFail.to_string(),
+        load_fail_reason: Some(parse_error.to_string()),
+        load_fail_kind: Some(parse_error.kind().to_string()),
+        load_fail_http_status: parse_error.http_status().map(|s| s as i32),
+        load_fail_stage: parse_error.stage(),
+        load_fail_attempts: word.load_fail_attempts + 1,
+        timestamp: time.timestamp(),
+        ..word.clone()
+    }
+}
+
+// A failed load changes only the word, definitions and relations stay as they were
 pub fn load_definitions(
     word: &Word,
     timestamp: i64,
-    definitions_result: &VortoResult<Vec<(Option<Voc>, String)>>,
+    page_result: &Result<ParsedPage, ParseError>,
+    current_definitions: &Vec<WordDefinition>,
+    vocs: &HashMap<String, Voc>,
+    source: &DefinitionSourceKind,
     time: &DateTime<Utc>,
-) -> VortoResult<(Word, Vec<WordDefinition>)> {
+) -> VortoResult<LoadedWord> {
     check_timestamp(word.timestamp, timestamp)?;
 
-    match definitions_result {
-        VortoResult::Err(_) => {
+    match page_result {
+        Err(parse_error) => VortoResult::Ok((
+            fail_load(word, parse_error, time),
+            current_definitions.clone(),
+            None,
+        )),
+        Ok(page) if page.definitions.is_empty() => VortoResult::Ok((
+            fail_load(word, &ParseError::NoDefinitions, time),
+            current_definitions.clone(),
+            None,
+        )),
+        Ok(page) => {
+            let loaded_definitions = loaded_definitions(word, page, vocs, source)?;
+            let word_relations = loaded_relations(word, &page.relations)?;
+        
             let new_word = Word {
-                load_status: WordLoadStatus::LoadedWithFail.to_string(),
+                load_status: WordLoadStatus::Loaded.to_string(),
+                load_fail_reason: None,
+                load_fail_kind: None,
+                load_fail_http_status: None,
+                load_fail_stage: None,
+                load_fail_attempts: 0,
+                synced_at: Some(time.naive_utc()),
+                has_upstream_changes: false,
+                part_of_speech: page
+                    .part_of_speech
+                    .map(|p| p.to_string())
+                    .or_else(|| word.part_of_speech.clone()),
+                gender: page
+                    .gender
+                    .map(|g| g.to_string())
+                    .or_else(|| word.gender.clone()),
+                syllables: page.syllables.clone().or_else(|| word.syllables.clone()),
                 timestamp: time.timestamp(),
                 ..word.clone()
             };
+        
+            VortoResult::Ok((
+                new_word,
+                merge_definitions(current_definitions, &loaded_definitions, source),
+                Some(word_relations),
+            ))
+        }
+    }
+}
 
-            VortoResult::Ok((new_word, vec![]))
-        },
-        VortoResult::Ok(definitions) if definitions.is_empty() => {
+#[derive(Debug, Default)]
+pub struct DefinitionsDiff {
+    pub added: Vec<WordDefinition>,
+    // Flagged as vanished
+    pub removed: Vec<WordDefinition>,
+    // Current and merged one, e.g. a vanished definition found on the page again
+    pub changed: Vec<(WordDefinition, WordDefinition)>,
+}
+
+// What the merge of a load does to the current definitions. Merged ones keep the id,
+// so a reworded definition is a new one and the old one is flagged
+fn diff_definitions(
+    current_definitions: &Vec<WordDefinition>,
+    merged_definitions: &Vec<WordDefinition>,
+) -> DefinitionsDiff {
+    let current: HashMap<i32, &WordDefinition> =
+        current_definitions.iter().map(|wd| (wd.id, wd)).collect();
+    let with_current = || {
+        merged_definitions
+            .iter()
+            .filter_map(|wd| current.get(&wd.id).map(|c| (*c, wd)))
+    };
+
+    DefinitionsDiff {
+        added: merged_definitions
+            .iter()
+            .filter(|wd| !current.contains_key(&wd.id))
+            .cloned()
+            .collect(),
+        removed: with_current()
+            .filter(|(c, wd)| !c.is_vanished && wd.is_vanished)
+            .map(|(_, wd)| wd.clone())
+            .collect(),
+        changed: with_current()
+            .filter(|(c, wd)| {
+                c.definition != wd.definition
+                    || c.voc_id != wd.voc_id
+                    || (c.is_vanished && !wd.is_vanished)
+            })
+            .map(|(c, wd)| (c.clone(), wd.clone()))
+            .collect(),
+    }
+}
+
+// What a load would do to the word, nothing is saved
+pub fn preview_definitions(
+    word: &Word,
+    page_result: &Result<ParsedPage, ParseError>,
+    current_definitions: &Vec<WordDefinition>,
+    vocs: &HashMap<String, Voc>,
+    source: &DefinitionSourceKind,
+    time: &DateTime<Utc>,
+) -> VortoResult<(Word, Vec<WordDefinition>, DefinitionsDiff)> {
+    let (new_word, new_word_definitions, _) = load_definitions(
+        word,
+        word.timestamp,
+        page_result,
+        current_definitions,
+        vocs,
+        source,
+        time,
+    )?;
+    let diff = diff_definitions(current_definitions, &new_word_definitions);
+
+    VortoResult::Ok((new_word, new_word_definitions, diff))
+}
+
+// Result of a scheduled re-fetch, None when the page brings nothing new. Words edited after
+// load are only flagged, the rest get the same merge as a load and are flagged for a review
+pub fn sync_definitions(
+    word: &Word,
+    page_result: &Result<ParsedPage, ParseError>,
+    current_definitions: &Vec<WordDefinition>,
+    vocs: &HashMap<String, Voc>,
+    source: &DefinitionSourceKind,
+    time: &DateTime<Utc>,
+) -> VortoResult<Option<LoadedWord>> {
+    let has_changes = match page_result {
+        // The merge leaves definitions of editors and other sources as they are
+        Ok(page) if !page.definitions.is_empty() => {
+            let merged_definitions = merge_definitions(
+                current_definitions,
+                &loaded_definitions(word, page, vocs, source)?,
+                source,
+            );
+            let diff = diff_definitions(current_definitions, &merged_definitions);
+            !diff.added.is_empty() || !diff.removed.is_empty() || !diff.changed.is_empty()
+        }
+        _ => true,
+    };
+    if !has_changes {
+        return VortoResult::Ok(None);
+    }
+
+    match page_result {
+        Ok(page) if !word.is_edited_after_load && !page.definitions.is_empty() => {
+            let (new_word, new_word_definitions, new_word_relations) = load_definitions(
+                word,
+                word.timestamp,
+                page_result,
+                current_definitions,
+                vocs,
+                source,
+                time,
+            )?;
             let new_word = Word {
-                load_status: WordLoadStatus::LoadedWithFail.to_string(),
-                timestamp: time.timestamp(),
-                ..word.clone()
+                has_upstream_changes: true,
+                ..new_word
             };
 
-            VortoResult::Ok((new_word, vec![]))
-        },
-        VortoResult::Ok(definitions) => {
-            let word_definitions = reduce_results(
-                &definitions
-                    .iter()
-                    .enumerate()
-                    .map(|(order, (voc, def))| {
-                        word_definition::new(
-                            -1,
-                            &def,
-                            &WordDefinitionStatus::NotActive,
-                            order as i32,
-                            word.id,
-                            voc.as_ref().map(|v| v.id),
-                        )
-                    })
-                    .collect::<Vec<_>>(),
-            )?;
-        
+            VortoResult::Ok(Some((new_word, new_word_definitions, new_word_relations)))
+        }
+        _ => {
             let new_word = Word {
-                load_status: WordLoadStatus::Loaded.to_string(),
+                has_upstream_changes: true,
+                synced_at: Some(time.naive_utc()),
                 timestamp: time.timestamp(),
                 ..word.clone()
             };
-        
-            VortoResult::Ok((new_word, word_definitions))
+
+            VortoResult::Ok(Some((new_word, current_definitions.clone(), None)))
         }
     }
 }
 
+// Only changed definitions are returned. Activating definitions is an edit after load,
+// the other actions don't touch what a reload merges
+pub fn bulk_edit(
+    word: &Word,
+    word_definitions: &Vec<WordDefinition>,
+    action: &BulkEditAction,
+    timestamp: i64,
+    time: &DateTime<Utc>,
+) -> VortoResult<(Word, Vec<WordDefinition>)> {
+    check_timestamp(word.timestamp, timestamp)?;
+    let word = Word {
+        timestamp: time.timestamp(),
+        ..word.clone()
+    };
+
+    match action {
+        BulkEditAction::SetStatus { status } => VortoResult::Ok((
+            Word {
+                status: status.to_string(),
+                ..word
+            },
+            vec![],
+        )),
+        BulkEditAction::SetDifficulty { difficulty } => VortoResult::Ok((
+            Word {
+                difficulty: *difficulty,
+                ..word
+            },
+            vec![],
+        )),
+        BulkEditAction::AddTags { tags } => {
+            let tags = normalize_tags(&word.tags.iter().chain(tags.iter()).cloned().collect());
+            validate_tags(&tags)?;
+
+            VortoResult::Ok((Word { tags, ..word }, vec![]))
+        }
+        BulkEditAction::RemoveTags { tags } => {
+            let removed_tags = normalize_tags(tags);
+            let tags = word
+                .tags
+                .iter()
+                .filter(|t| !removed_tags.contains(t))
+                .cloned()
+                .collect();
+
+            VortoResult::Ok((Word { tags, ..word }, vec![]))
+        }
+        BulkEditAction::ActivateDefinitions => {
+            let active = WordDefinitionStatus::Active.to_string();
+            let new_word_definitions = word_definitions
+                .iter()
+                .filter(|wd| !wd.is_vanished && wd.status != active)
+                .map(|wd| WordDefinition {
+                    status: active.clone(),
+                    ..wd.clone()
+                })
+                .collect();
+
+            VortoResult::Ok((
+                Word {
+                    is_edited_after_load: true,
+                    ..word
+                },
+                new_word_definitions,
+            ))
+        }
+    }
+}
+
+// Goes through the same checks as an edit
+pub fn revert(
+    word: &Word,
+    snapshot: &WordSnapshot,
+    timestamp: i64,
+    time: &DateTime<Utc>,
+) -> VortoResult<(Word, Vec<WordDefinition>)> {
+    let word_update = WordUpdate {
+        status: &snapshot.status,
+        difficulty: snapshot.difficulty,
+        part_of_speech: Some(snapshot.part_of_speech.as_ref()),
+        gender: Some(snapshot.gender.as_ref()),
+        definitions: &snapshot.definitions,
+    };
+    let (new_word, new_word_definitions) = update(word, &word_update, timestamp, time)?;
+
+    VortoResult::Ok((
+        Word {
+            tags: snapshot.tags.clone(),
+            ..new_word
+        },
+        new_word_definitions,
+    ))
+}
+
+pub fn resolve_upstream_changes(
+    word: &Word,
+    timestamp: i64,
+    time: &DateTime<Utc>,
+) -> VortoResult<Word> {
+    check_timestamp(word.timestamp, timestamp)?;
+    validate_fn(
+        || !word.has_upstream_changes,
+        VortoError::new(
+            VortoErrorCode::Validation,
+            "Word has no upstream changes".to_owned(),
+        ),
+    )?;
+
+    VortoResult::Ok(Word {
+        has_upstream_changes: false,
+        timestamp: time.timestamp(),
+        ..word.clone()
+    })
+}
+
+// An editor's change of a word. Part of speech and gender are kept when None
+pub struct WordUpdate<'a> {
+    pub status: &'a WordStatus,
+    pub difficulty: i32,
+    pub part_of_speech: Option<Option<&'a PartOfSpeech>>,
+    pub gender: Option<Option<&'a Gender>>,
+    pub definitions: &'a Vec<WordDefinitionDTO>,
+}
+
 pub fn update(
     word: &Word,
-    new_status: &WordStatus,
-    difficulty: i32,
+    word_update: &WordUpdate,
     timestamp: i64,
-    word_definitions: &Vec<WordDefinitionDTO>,
     time: &DateTime<Utc>,
 ) -> VortoResult<(Word, Vec<WordDefinition>)> {
     check_timestamp(word.timestamp, timestamp)?;
 
     let new_word_definitions = reduce_results(
-        &word_definitions
+        &word_update
+            .definitions
             .iter()
             .enumerate()
             .map(|(order, wd)| {
                 word_definition::new(
                     -1,
-                    &wd.definition,
-                    &wd.status,
                     order as i32,
                     word.id,
-                    wd.voc_id
+                    &WordDefinitionParams {
+                        definition: &wd.definition,
+                        status: &wd.status,
+                        voc_id: wd.voc_id,
+                        source: wd.source.as_ref(),
+                        examples: &wd.examples,
+                        is_vanished: wd.is_vanished,
+                    },
                 )
             })
             .collect::<Vec<_>>(),
     )?;
 
     let new_word = Word {
-        status: new_status.to_string(),
+        status: word_update.status.to_string(),
+        is_edited_after_load: true,
         timestamp: time.timestamp(),
-        difficulty,
+        difficulty: word_update.difficulty,
+        part_of_speech: match word_update.part_of_speech {
+            Some(p) => p.map(|p| p.to_string()),
+            None => word.part_of_speech.clone(),
+        },
+        gender: match word_update.gender {
+            Some(g) => g.map(|g| g.to_string()),
+            None => word.gender.clone(),
+        },
         ..word.clone()
     };
 
     VortoResult::Ok((new_word, new_word_definitions))
 }
 
-#[allow(dead_code)]
+// Words from the admin panel are checked the same way as the migrated ones,
+// a word gets archived only through the archive flow
+pub fn create(
+    body: &str,
+    status: WordStatus,
+    difficulty: i32,
+    part_of_speech: Option<&PartOfSpeech>,
+    gender: Option<&Gender>,
+    tags: &Vec<String>,
+    time: &DateTime<Utc>,
+) -> VortoResult<Word> {
+    validate_fn(
+        || status == WordStatus::Archived,
+        VortoError::new(
+            VortoErrorCode::Validation,
+            "Word can't be created archived".to_owned(),
+        ),
+    )?;
+    let tags = normalize_tags(tags);
+    validate_tags(&tags)?;
+
+    let word = new(
+        -1,
+        &clean_body(body),
+        status,
+        false,
+        WordLoadStatus::NotLoaded,
+        difficulty,
+        time.timestamp(),
+    )?;
+
+    VortoResult::Ok(Word {
+        part_of_speech: part_of_speech.map(|p| p.to_string()),
+        gender: gender.map(|g| g.to_string()),
+        tags,
+        ..word
+    })
+}
+
+// New words of an import and the bodies skipped as already known or repeated,
+// rows are numbered in the errors
+pub fn import(
+    rows: &Vec<(usize, ImportedWordDTO)>,
+    status: Option<WordStatus>,
+    difficulty: Option<i32>,
+    tags: &Vec<String>,
+    existing_bodies: &Vec<String>,
+    time: &DateTime<Utc>,
+) -> VortoResult<(Vec<Word>, Vec<String>)> {
+    let words = reduce_results(
+        &rows
+            .iter()
+            .map(|(row, w)| {
+                match create(
+                    &w.body,
+                    w.status.or(status).unwrap_or(WordStatus::Draft),
+                    w.difficulty.or(difficulty).unwrap_or(DEFAULT_DIFFICULTY),
+                    None,
+                    None,
+                    &tags.iter().chain(w.tags.iter()).cloned().collect(),
+                    time,
+                ) {
+                    VortoResult::Err(e) => VortoResult::Err(VortoError::new(
+                        VortoErrorCode::Validation,
+                        format!("Row {}: {}", row, e.message),
+                    )),
+                    word => word,
+                }
+            })
+            .collect(),
+    )?;
+
+    let mut known_bodies: HashSet<String> =
+        existing_bodies.iter().map(|b| normalize_body(b)).collect();
+    let (new_words, duplicates): (Vec<Word>, Vec<Word>) = words
+        .into_iter()
+        .partition(|w| known_bodies.insert(normalize_body(&w.body)));
+
+    VortoResult::Ok((
+        new_words,
+        duplicates.into_iter().map(|w| w.body).collect(),
+    ))
+}
+
+pub fn archive(word: &Word, timestamp: i64, time: &DateTime<Utc>) -> VortoResult<Word> {
+    check_timestamp(word.timestamp, timestamp)?;
+    validate_fn(
+        || word.status == WordStatus::Archived.to_string(),
+        VortoError::new(
+            VortoErrorCode::Validation,
+            "Word is already archived".to_owned(),
+        ),
+    )?;
+
+    VortoResult::Ok(Word {
+        status: WordStatus::Archived.to_string(),
+        timestamp: time.timestamp(),
+        ..word.clone()
+    })
+}
+
 pub fn new(
     id: i32,
     body: &str,
@@ -168,5 +743,398 @@ pub fn new(
         load_status: load_status.to_string(),
         difficulty,
         timestamp,
+        load_fail_reason: None,
+        part_of_speech: None,
+        gender: None,
+        syllables: None,
+        load_fail_kind: None,
+        load_fail_http_status: None,
+        load_fail_stage: None,
+        load_fail_attempts: 0,
+        synced_at: None,
+        has_upstream_changes: false,
+        tags: vec![],
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WIKTIONARY: Option<DefinitionSourceKind> = Some(DefinitionSourceKind::Wiktionary);
+    const EN_WIKTIONARY: Option<DefinitionSourceKind> = Some(DefinitionSourceKind::EnWiktionary);
+
+    fn word() -> Word {
+        new(
+            1,
+            "кот",
+            WordStatus::Active,
+            false,
+            WordLoadStatus::Loaded,
+            1,
+            100,
+        )
+        .unwrap()
+        .clone()
+    }
+
+    fn definition(
+        id: i32,
+        text: &str,
+        source: Option<DefinitionSourceKind>,
+        is_vanished: bool,
+    ) -> WordDefinition {
+        word_definition::new(
+            id,
+            0,
+            1,
+            &WordDefinitionParams {
+                definition: text,
+                status: &WordDefinitionStatus::NotActive,
+                voc_id: None,
+                source: source.as_ref(),
+                examples: &vec![],
+                is_vanished,
+            },
+        )
+        .unwrap()
+        .clone()
+    }
+
+    fn page(definitions: &[&str]) -> Result<ParsedPage, ParseError> {
+        Ok(ParsedPage {
+            definitions: definitions
+                .iter()
+                .map(|d| ParsedDefinition {
+                    definition: d.to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        })
+    }
+
+    fn states(word_definitions: &[WordDefinition]) -> Vec<(i32, String, bool)> {
+        word_definitions
+            .iter()
+            .map(|wd| (wd.id, wd.definition.clone(), wd.is_vanished))
+            .collect()
+    }
+
+    fn ids(word_definitions: &[WordDefinition]) -> Vec<i32> {
+        word_definitions.iter().map(|wd| wd.id).collect()
+    }
+
+    fn state(id: i32, text: &str, is_vanished: bool) -> (i32, String, bool) {
+        (id, text.to_owned(), is_vanished)
+    }
+
+    #[test]
+    fn merges_definitions() {
+        let cases = vec![
+            (
+                "kept",
+                vec![definition(1, "самец кошки", WIKTIONARY, false)],
+                vec![definition(-1, "Самец  кошки", WIKTIONARY, false)],
+                vec![state(1, "самец кошки", false)],
+            ),
+            (
+                "new",
+                vec![definition(1, "самец кошки", WIKTIONARY, false)],
+                vec![
+                    definition(-1, "самец кошки", WIKTIONARY, false),
+                    definition(-1, "морской котик", WIKTIONARY, false),
+                ],
+                vec![
+                    state(1, "самец кошки", false),
+                    state(-1, "морской котик", false),
+                ],
+            ),
+            (
+                "vanished upstream",
+                vec![definition(1, "самец кошки", WIKTIONARY, false)],
+                vec![definition(-1, "морской котик", WIKTIONARY, false)],
+                vec![
+                    state(1, "самец кошки", true),
+                    state(-1, "морской котик", false),
+                ],
+            ),
+            (
+                "back upstream",
+                vec![definition(1, "самец кошки", WIKTIONARY, true)],
+                vec![definition(-1, "самец кошки", WIKTIONARY, false)],
+                vec![state(1, "самец кошки", false)],
+            ),
+            (
+                "other sources",
+                vec![
+                    definition(1, "добавлено редактором", None, false),
+                    definition(2, "male cat animal", EN_WIKTIONARY, false),
+                    definition(3, "tomcat, vanished", EN_WIKTIONARY, true),
+                ],
+                vec![definition(-1, "самец кошки", WIKTIONARY, false)],
+                vec![
+                    state(1, "добавлено редактором", false),
+                    state(2, "male cat animal", false),
+                    state(3, "tomcat, vanished", true),
+                    state(-1, "самец кошки", false),
+                ],
+            ),
+        ];
+
+        for (name, current, loaded, expected) in cases {
+            let merged = merge_definitions(&current, &loaded, &DefinitionSourceKind::Wiktionary);
+
+            assert_eq!(states(&merged), expected, "{}", name);
+            assert_eq!(
+                merged.iter().map(|wd| wd.order).collect::<Vec<_>>(),
+                (0..expected.len() as i32).collect::<Vec<_>>(),
+                "{}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn diffs_definitions() {
+        let cases = vec![
+            (
+                "kept",
+                vec![definition(1, "самец кошки", WIKTIONARY, false)],
+                vec![definition(1, "самец кошки", WIKTIONARY, false)],
+                (vec![], vec![], vec![]),
+            ),
+            (
+                "new",
+                vec![definition(1, "самец кошки", WIKTIONARY, false)],
+                vec![
+                    definition(1, "самец кошки", WIKTIONARY, false),
+                    definition(-1, "морской котик", WIKTIONARY, false),
+                ],
+                (vec![-1], vec![], vec![]),
+            ),
+            (
+                "vanished upstream",
+                vec![definition(1, "самец кошки", WIKTIONARY, false)],
+                vec![definition(1, "самец кошки", WIKTIONARY, true)],
+                (vec![], vec![1], vec![]),
+            ),
+            (
+                "back upstream",
+                vec![definition(1, "самец кошки", WIKTIONARY, true)],
+                vec![definition(1, "самец кошки", WIKTIONARY, false)],
+                (vec![], vec![], vec![1]),
+            ),
+            (
+                "other sources",
+                vec![
+                    definition(1, "добавлено редактором", None, false),
+                    definition(2, "tomcat, vanished", EN_WIKTIONARY, true),
+                ],
+                vec![
+                    definition(1, "добавлено редактором", None, false),
+                    definition(2, "tomcat, vanished", EN_WIKTIONARY, true),
+                ],
+                (vec![], vec![], vec![]),
+            ),
+        ];
+
+        for (name, current, merged, (added, removed, changed)) in cases {
+            let diff = diff_definitions(&current, &merged);
+
+            assert_eq!(ids(&diff.added), added, "{}", name);
+            assert_eq!(ids(&diff.removed), removed, "{}", name);
+            assert_eq!(
+                diff.changed.iter().map(|(c, _)| c.id).collect::<Vec<_>>(),
+                changed,
+                "{}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn syncs_definitions() {
+        let edited_word = Word {
+            is_edited_after_load: true,
+            ..word()
+        };
+        let cases = vec![
+            (
+                "kept",
+                word(),
+                vec![definition(1, "самец кошки", WIKTIONARY, false)],
+                page(&["самец кошки"]),
+                None,
+            ),
+            (
+                "new",
+                word(),
+                vec![definition(1, "самец кошки", WIKTIONARY, false)],
+                page(&["самец кошки", "морской котик"]),
+                Some(vec![
+                    state(1, "самец кошки", false),
+                    state(-1, "морской котик", false),
+                ]),
+            ),
+            (
+                "vanished upstream",
+                word(),
+                vec![
+                    definition(1, "самец кошки", WIKTIONARY, false),
+                    definition(2, "морской котик", WIKTIONARY, false),
+                ],
+                page(&["самец кошки"]),
+                Some(vec![
+                    state(1, "самец кошки", false),
+                    state(2, "морской котик", true),
+                ]),
+            ),
+            (
+                "back upstream",
+                word(),
+                vec![definition(1, "самец кошки", WIKTIONARY, true)],
+                page(&["самец кошки"]),
+                Some(vec![state(1, "самец кошки", false)]),
+            ),
+            (
+                "other sources",
+                word(),
+                vec![
+                    definition(1, "добавлено редактором", None, false),
+                    definition(2, "male cat animal", EN_WIKTIONARY, false),
+                    definition(3, "самец кошки", WIKTIONARY, false),
+                ],
+                page(&["самец кошки"]),
+                None,
+            ),
+            (
+                "edited after load",
+                edited_word,
+                vec![definition(1, "самец кошки", WIKTIONARY, false)],
+                page(&["морской котик"]),
+                Some(vec![state(1, "самец кошки", false)]),
+            ),
+            (
+                "failed fetch",
+                word(),
+                vec![definition(1, "самец кошки", WIKTIONARY, false)],
+                Err(ParseError::PageNotFound),
+                Some(vec![state(1, "самец кошки", false)]),
+            ),
+        ];
+
+        let now = Utc::now();
+        for (name, word, current, page_result, expected) in cases {
+            let synced = sync_definitions(
+                &word,
+                &page_result,
+                &current,
+                &HashMap::new(),
+                &DefinitionSourceKind::Wiktionary,
+                &now,
+            )
+            .unwrap()
+            .clone();
+
+            assert_eq!(
+                synced.as_ref().map(|(_, wds, _)| states(wds)),
+                expected,
+                "{}",
+                name
+            );
+            if let Some((new_word, _, _)) = synced {
+                assert!(new_word.has_upstream_changes, "{}", name);
+                assert_eq!(new_word.timestamp, now.timestamp(), "{}", name);
+            }
+        }
+    }
+
+    #[test]
+    fn bulk_edits() {
+        let word = Word {
+            tags: vec!["животные".to_owned()],
+            ..word()
+        };
+        let active = WordDefinition {
+            status: WordDefinitionStatus::Active.to_string(),
+            ..definition(3, "домашнее животное", WIKTIONARY, false)
+        };
+        let word_definitions = vec![
+            definition(1, "самец кошки", WIKTIONARY, false),
+            definition(2, "морской котик", WIKTIONARY, true),
+            active,
+            definition(4, "добавлено редактором", None, false),
+        ];
+        let cases = vec![
+            (
+                BulkEditAction::SetStatus {
+                    status: WordStatus::Draft,
+                },
+                "draft",
+                vec!["животные"],
+                false,
+                vec![],
+            ),
+            (
+                BulkEditAction::AddTags {
+                    tags: vec![" Звери ".to_owned(), "животные".to_owned()],
+                },
+                "active",
+                vec!["животные", "звери"],
+                false,
+                vec![],
+            ),
+            (
+                BulkEditAction::RemoveTags {
+                    tags: vec!["Животные".to_owned()],
+                },
+                "active",
+                vec![],
+                false,
+                vec![],
+            ),
+            (
+                BulkEditAction::ActivateDefinitions,
+                "active",
+                vec!["животные"],
+                true,
+                vec![1, 4],
+            ),
+        ];
+
+        let now = Utc::now();
+        for (action, status, tags, is_edited_after_load, activated) in cases {
+            let (new_word, new_word_definitions) =
+                bulk_edit(&word, &word_definitions, &action, word.timestamp, &now)
+                    .unwrap()
+                    .clone();
+
+            assert_eq!(new_word.status, status, "{:?}", action);
+            assert_eq!(new_word.tags, tags, "{:?}", action);
+            assert_eq!(
+                new_word.is_edited_after_load, is_edited_after_load,
+                "{:?}",
+                action
+            );
+            assert_eq!(new_word.timestamp, now.timestamp(), "{:?}", action);
+            assert_eq!(ids(&new_word_definitions), activated, "{:?}", action);
+            assert!(new_word_definitions
+                .iter()
+                .all(|wd| wd.status == WordDefinitionStatus::Active.to_string()));
+        }
+    }
+
+    #[test]
+    fn bulk_edit_fails_on_changed_word() {
+        let result = bulk_edit(
+            &word(),
+            &vec![],
+            &BulkEditAction::ActivateDefinitions,
+            99,
+            &Utc::now(),
+        );
+
+        assert!(result.is_err());
+    }
+}
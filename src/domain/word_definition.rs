@@ -1,6 +1,9 @@
 use crate::error::{VortoError, VortoErrorCode, VortoResult};
 
-use super::{common::validate_fn, enums::WordDefinitionStatus};
+use super::{
+    common::validate_fn,
+    enums::{DefinitionSourceKind, WordDefinitionStatus},
+};
 
 #[derive(Clone, Debug)]
 pub struct WordDefinition {
@@ -10,14 +13,17 @@ pub struct WordDefinition {
     pub order: i32,
     pub word_id: i32,
     pub voc_id: Option<i32>,
+    pub source: Option<String>,
+    pub examples: Vec<String>,
+    // Was loaded before, but the last load didn't find it on the page
+    pub is_vanished: bool,
 }
 
+pub const EXAMPLE_MAX_LEN: usize = 2000;
+
 fn validate_definition(definition: &str) -> VortoResult<()> {
     validate_fn(
-        || {
-            let definition_len = definition.len();
-            definition_len < 10 || definition_len > 1000
-        },
+        || !(10..=1000).contains(&definition.len()),
         VortoError::new(
             VortoErrorCode::Validation,
             "Definition's lenght should be in range 10-1000".to_owned(),
@@ -25,22 +31,55 @@ fn validate_definition(definition: &str) -> VortoResult<()> {
     )
 }
 
+// Same definition text with another case or spacing is still the same definition
+pub fn normalize(definition: &str) -> String {
+    definition
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+fn validate_example(example: &str) -> VortoResult<()> {
+    validate_fn(
+        || example.is_empty() || example.len() > EXAMPLE_MAX_LEN,
+        VortoError::new(
+            VortoErrorCode::Validation,
+            format!("Example's length should be in range 1-{}", EXAMPLE_MAX_LEN),
+        ),
+    )
+}
+
+// What a definition is made of, its place in a word is given apart
+pub struct WordDefinitionParams<'a> {
+    pub definition: &'a str,
+    pub status: &'a WordDefinitionStatus,
+    pub voc_id: Option<i32>,
+    pub source: Option<&'a DefinitionSourceKind>,
+    pub examples: &'a Vec<String>,
+    pub is_vanished: bool,
+}
+
 pub fn new(
     id: i32,
-    definition: &str,
-    status: &WordDefinitionStatus,
     order: i32,
     word_id: i32,
-    voc_id: Option<i32>,
+    params: &WordDefinitionParams,
 ) -> VortoResult<WordDefinition> {
-    validate_definition(definition)?;
+    validate_definition(params.definition)?;
+    for example in params.examples {
+        validate_example(example)?;
+    }
 
     VortoResult::Ok(WordDefinition {
         id,
-        definition: definition.to_owned(),
-        status: status.to_string(),
+        definition: params.definition.to_owned(),
+        status: params.status.to_string(),
         order,
         word_id,
-        voc_id,
+        voc_id: params.voc_id,
+        source: params.source.map(|s| s.to_string()),
+        examples: params.examples.clone(),
+        is_vanished: params.is_vanished,
     })
 }
@@ -0,0 +1,40 @@
+use crate::error::{VortoError, VortoErrorCode, VortoResult};
+
+use super::{common::validate_fn, enums::WordRelationKind};
+
+#[derive(Clone, Debug, sqlx::FromRow)]
+pub struct WordRelation {
+    pub id: i32,
+    pub kind: String,
+    pub body: String,
+    pub order: i32,
+    pub word_id: i32,
+}
+
+fn validate_body(body: &str) -> VortoResult<()> {
+    validate_fn(
+        || body.is_empty() || body.len() > 255,
+        VortoError::new(
+            VortoErrorCode::Validation,
+            "Relation's length should be in range 1-255".to_owned(),
+        ),
+    )
+}
+
+pub fn new(
+    id: i32,
+    kind: &WordRelationKind,
+    body: &str,
+    order: i32,
+    word_id: i32,
+) -> VortoResult<WordRelation> {
+    validate_body(body)?;
+
+    VortoResult::Ok(WordRelation {
+        id,
+        kind: kind.to_string(),
+        body: body.to_owned(),
+        order,
+        word_id,
+    })
+}
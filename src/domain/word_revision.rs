@@ -0,0 +1,106 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{VortoError, VortoErrorCode, VortoResult};
+
+use super::{
+    common::validate_fn,
+    enums::{
+        DefinitionSourceKind, Gender, PartOfSpeech, WordDefinitionStatus, WordRevisionAction,
+        WordStatus,
+    },
+    word::{Word, WordDefinitionDTO},
+    word_definition::WordDefinition,
+};
+
+// `before` is empty for a created word, snapshots are stored as JSON
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct WordRevision {
+    pub id: i32,
+    pub word_id: i32,
+    // Empty for a load, sync or reparse
+    pub user_id: Option<i32>,
+    pub action: String,
+    pub before: Option<String>,
+    pub after: String,
+    pub created_at: NaiveDateTime,
+}
+
+// What an editor can change on a word
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct WordSnapshot {
+    pub status: WordStatus,
+    pub difficulty: i32,
+    pub part_of_speech: Option<PartOfSpeech>,
+    pub gender: Option<Gender>,
+    pub tags: Vec<String>,
+    pub definitions: Vec<WordDefinitionDTO>,
+}
+
+pub fn snapshot(word: &Word, word_definitions: &Vec<WordDefinition>) -> WordSnapshot {
+    WordSnapshot {
+        status: WordStatus::from_str(&word.status).unwrap(),
+        difficulty: word.difficulty,
+        part_of_speech: word
+            .part_of_speech
+            .as_ref()
+            .map(|p| PartOfSpeech::from_str(p).unwrap()),
+        gender: word.gender.as_ref().map(|g| Gender::from_str(g).unwrap()),
+        tags: word.tags.clone(),
+        definitions: word_definitions
+            .iter()
+            .sorted_by_key(|wd| wd.order)
+            .map(|wd| WordDefinitionDTO {
+                definition: wd.definition.clone(),
+                status: WordDefinitionStatus::from_str(&wd.status).unwrap(),
+                voc_id: wd.voc_id,
+                source: wd
+                    .source
+                    .as_ref()
+                    .map(|s| DefinitionSourceKind::from_str(s).unwrap()),
+                examples: wd.examples.clone(),
+                is_vanished: wd.is_vanished,
+            })
+            .collect(),
+    }
+}
+
+pub fn new(
+    word_id: i32,
+    user_id: Option<i32>,
+    action: &WordRevisionAction,
+    before: Option<&WordSnapshot>,
+    after: &WordSnapshot,
+    time: &DateTime<Utc>,
+) -> VortoResult<WordRevision> {
+    let before = match before {
+        Some(before) => Some(serde_json::to_string(before)?),
+        None => None,
+    };
+
+    VortoResult::Ok(WordRevision {
+        id: -1,
+        word_id,
+        user_id,
+        action: action.to_string(),
+        before,
+        after: serde_json::to_string(after)?,
+        created_at: time.naive_utc(),
+    })
+}
+
+// Every revision is a version of the word, the one it was left in is brought back
+pub fn restorable_snapshot(revision: &WordRevision, word_id: i32) -> VortoResult<WordSnapshot> {
+    validate_fn(
+        || revision.word_id != word_id,
+        VortoError::new(
+            VortoErrorCode::NotFound,
+            format!("Word {} has no revision {}", word_id, revision.id),
+        ),
+    )?;
+
+    VortoResult::Ok(serde_json::from_str(&revision.after)?)
+}
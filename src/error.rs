@@ -1,9 +1,12 @@
 use std::{
     cell::BorrowError,
     convert::Infallible,
+    fmt,
     ops::{ControlFlow, FromResidual, Try},
 };
 
+use crate::domain::enums::LoadFailKind;
+
 #[derive(Debug)]
 pub enum VortoErrorCode {
     Timestamp = 1,
@@ -15,6 +18,7 @@ pub enum VortoErrorCode {
     TooManyWords = 7,
     InvalidGameToken = 8,
     InvalidLoginOrPassword = 9,
+    Parse = 10,
     Infrastructure = 1000,
 }
 
@@ -33,6 +37,97 @@ impl VortoError {
     }
 }
 
+// Why a dictionary page did not give definitions, kept as a value so it can be stored with the word
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseError {
+    Unavailable(String),
+    HttpStatus(u16),
+    PageNotFound,
+    Redirect(String),
+    MissingSection(String),
+    MissingNode { node: String, stage: String },
+    InvalidContent(String),
+    NoDefinitions,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Unavailable(message) => write!(f, "Page is unavailable: {}", message),
+            ParseError::HttpStatus(status) => write!(f, "Page responded with HTTP {}", status),
+            ParseError::PageNotFound => write!(f, "Page not found"),
+            ParseError::Redirect(target) => write!(f, "Page redirects to '{}'", target),
+            ParseError::MissingSection(section) => write!(f, "No '{}' section", section),
+            ParseError::MissingNode { node, stage } => {
+                write!(f, "Node '{}' not found in '{}'", node, stage)
+            }
+            ParseError::InvalidContent(message) => write!(f, "Invalid page content: {}", message),
+            ParseError::NoDefinitions => write!(f, "No definitions found"),
+        }
+    }
+}
+
+impl ParseError {
+    // Page could be there next time, worth asking again later
+    pub fn is_transient(&self) -> bool {
+        match self {
+            ParseError::Unavailable(_) => true,
+            ParseError::HttpStatus(status) => *status == 429 || *status >= 500,
+            _ => false,
+        }
+    }
+
+    pub fn kind(&self) -> LoadFailKind {
+        match self {
+            ParseError::Unavailable(_) => LoadFailKind::Unavailable,
+            ParseError::HttpStatus(_) => LoadFailKind::HttpStatus,
+            ParseError::PageNotFound => LoadFailKind::PageNotFound,
+            ParseError::Redirect(_) => LoadFailKind::Redirect,
+            ParseError::MissingSection(_) => LoadFailKind::MissingSection,
+            ParseError::MissingNode { .. } => LoadFailKind::MissingNode,
+            ParseError::InvalidContent(_) => LoadFailKind::InvalidContent,
+            ParseError::NoDefinitions => LoadFailKind::NoDefinitions,
+        }
+    }
+
+    pub fn http_status(&self) -> Option<u16> {
+        match self {
+            ParseError::HttpStatus(status) => Some(*status),
+            _ => None,
+        }
+    }
+
+    // Where the parser gave up, a section heading or a parser step
+    pub fn stage(&self) -> Option<String> {
+        match self {
+            ParseError::MissingSection(section) => Some(section.clone()),
+            ParseError::MissingNode { stage, .. } => Some(stage.clone()),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for ParseError {
+    fn from(e: reqwest::Error) -> Self {
+        match e.status() {
+            Some(status) => ParseError::HttpStatus(status.as_u16()),
+            None => ParseError::Unavailable(e.to_string()),
+        }
+    }
+}
+
+impl From<std::io::Error> for ParseError {
+    fn from(e: std::io::Error) -> Self {
+        ParseError::Unavailable(e.to_string())
+    }
+}
+
+impl From<serde_json::Error> for ParseError {
+    fn from(e: serde_json::Error) -> Self {
+        ParseError::InvalidContent(e.to_string())
+    }
+}
+
 #[derive(Debug)]
 pub enum VortoResult<T> {
     Ok(T),
@@ -156,6 +251,18 @@ impl<T> FromResidual<Result<Infallible, std::io::Error>> for VortoResult<T> {
     }
 }
 
+impl<T> FromResidual<Result<Infallible, serde_json::Error>> for VortoResult<T> {
+    fn from_residual(x: Result<Infallible, serde_json::Error>) -> Self {
+        match x {
+            Err(e) => VortoResult::Err(VortoError::new(
+                VortoErrorCode::Infrastructure,
+                e.to_string(),
+            )),
+            Ok(_) => panic!("unreachable"),
+        }
+    }
+}
+
 impl<T> FromResidual<Result<Infallible, jsonwebtokens::error::Error>> for VortoResult<T> {
     fn from_residual(x: Result<Infallible, jsonwebtokens::error::Error>) -> Self {
         match x {
@@ -168,10 +275,19 @@ impl<T> FromResidual<Result<Infallible, jsonwebtokens::error::Error>> for VortoR
     }
 }
 
+impl<T> FromResidual<Result<Infallible, ParseError>> for VortoResult<T> {
+    fn from_residual(x: Result<Infallible, ParseError>) -> Self {
+        match x {
+            Err(e) => VortoResult::Err(VortoError::new(VortoErrorCode::Parse, e.to_string())),
+            Ok(_) => panic!("unreachable"),
+        }
+    }
+}
+
 impl<T, U> FromResidual<VortoResult<U>> for VortoResult<T> {
     fn from_residual(x: VortoResult<U>) -> Self {
         match x {
-            VortoResult::Err(e) => VortoResult::Err(From::from(e)),
+            VortoResult::Err(e) => VortoResult::Err(e),
             VortoResult::Ok(_data) => panic!("unreachable"),
         }
     }
@@ -20,7 +20,6 @@ use rocket::fairing::{AdHoc, Fairing, Info, Kind};
 use rocket::http::{Header};
 use rocket::serde::json::Json;
 use routes::*;
-use services::wiki_parser_service;
 
 #[macro_use]
 extern crate rocket;
@@ -33,7 +32,12 @@ extern crate dotenv;
 #[macro_use]
 extern crate log;
 
+use crate::services::definition_source::DefinitionSources;
+use crate::services::dump_import_service;
+use crate::services::http_client::HttpClient;
+use crate::services::job_service;
 use crate::services::password_hasher::PwdHasher;
+use crate::services::sync_service;
 use crate::states::*;
 use rocket::response::{self, Responder};
 use rocket::{Request, Response};
@@ -90,19 +94,59 @@ async fn main() {
     dotenv().ok();
     env_logger::init();
 
+    // Offline definitions import: `vorto-back-rust import_dump <pages-articles.xml>`
+    let args = env::args().collect::<Vec<_>>();
+    if args.get(1).map(String::as_str) == Some("import_dump") {
+        let path = args.get(2).expect("Path to a Wiktionary pages-articles XML dump is missing");
+        let pool = pg_sqlx_conect().await;
+        match dump_import_service::import(path, &pool).await {
+            VortoResult::Ok(stats) => info!("Dump import completed: {:?}", stats),
+            VortoResult::Err(e) => error!("Dump import failed: {}", e.message),
+        }
+        return;
+    }
+
     rocket::build()
         .attach(CORS)
         .attach(AdHoc::on_liftoff("Seed DB", |rocket| {
             Box::pin(async move { seed::run(rocket).await })
         }))
+        .attach(AdHoc::on_liftoff("Job workers", |rocket| {
+            Box::pin(async move { job_service::start_workers(rocket) })
+        }))
+        .attach(AdHoc::on_liftoff("Re-sync scheduler", |rocket| {
+            Box::pin(async move { sync_service::start_scheduler(rocket) })
+        }))
         .mount(
             "/api/v1/admin",
             routes![
                 v1::admin::users::sign_in,
                 v1::admin::words::search,
                 v1::admin::words::load_definitions,
+                v1::admin::words::preview_definitions,
+                v1::admin::words::bulk_load,
+                v1::admin::words::bulk_load_status,
+                v1::admin::words::cancel_bulk_load,
+                v1::admin::words::queue_load_definitions,
+                v1::admin::words::reparse,
+                v1::admin::words::sync,
+                v1::admin::words::resolve_upstream_changes,
+                v1::admin::words::create,
+                v1::admin::words::import,
+                v1::admin::words::export,
+                v1::admin::words::archive,
                 v1::admin::words::update,
+                v1::admin::words::bulk_edit,
+                v1::admin::words::history,
+                v1::admin::words::revert,
                 v1::admin::words::word_stats,
+                v1::admin::jobs::search,
+                v1::admin::jobs::retry,
+                v1::admin::jobs::cancel,
+                v1::admin::searches::get_all,
+                v1::admin::searches::save,
+                v1::admin::searches::delete,
+                v1::admin::searches::run,
             ],
         )
         .mount(
@@ -113,11 +157,14 @@ async fn main() {
                 v1::vocs::get_vocs,
                 v1::game::create,
                 v1::game::complete_round,
+                v1::game::words,
                 v1::game::game_view
             ],
         )
         .manage(pg_sqlx_conect().await)
         .manage(PwdHasher::new())
+        .manage(DefinitionSources::from_env())
+        .manage(HttpClient::from_env())
         .launch()
         .await
         .unwrap();
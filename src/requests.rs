@@ -1,6 +1,15 @@
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer, Serialize};
 
-use crate::{domain::{enums::{WordLoadStatus, WordStatus}, word::WordDefinitionDTO}, services::word_service::FieldOrder};
+use crate::{domain::{enums::{DefinitionSourceKind, ExportFormat, Gender, ImportFormat, JobKind, JobStatus, LoadFailKind, PartOfSpeech, TextSearchMode, WordLoadStatus, WordStatus}, word::{BulkEditAction, WordDefinitionDTO}}, services::word_service::{FieldOrder, WordCursor}};
+
+// Tells a left out field, None, from a null one, Some(None)
+fn deserialize_some<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
 
 #[derive(Deserialize)]
 pub struct LoginRequest {
@@ -8,21 +17,208 @@ pub struct LoginRequest {
     pub password: String,
 }
 
-#[derive(Deserialize, Debug)]
-pub struct SearchRequest {
+// Word conditions shared by the admin search and the operations over many words
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WordFilter {
     pub text: String,
+    #[serde(default)]
+    pub text_mode: TextSearchMode,
+    #[serde(default)]
+    pub ignore_case: bool,
+    // ё and е are taken as the same letter
+    #[serde(default)]
+    pub ignore_yo: bool,
     pub statuses: Vec<WordStatus>,
     pub load_statuses: Vec<WordLoadStatus>,
     pub difficulties: Vec<i32>,
-    pub field_order: FieldOrder,
+    #[serde(default)]
+    pub parts_of_speech: Vec<PartOfSpeech>,
+    #[serde(default)]
+    pub genders: Vec<Gender>,
+    #[serde(default)]
+    pub load_fail_kinds: Vec<LoadFailKind>,
+    // Section or parser step a load failed at, e.g. "Русский"
+    #[serde(default)]
+    pub load_fail_stages: Vec<String>,
+    // Words flagged by a re-sync for a review
+    #[serde(default)]
+    pub has_upstream_changes: Option<bool>,
+    #[serde(default)]
+    pub is_edited_after_load: Option<bool>,
+    // Active definitions that have not vanished upstream, max 0 gives words without any
+    #[serde(default)]
+    pub min_active_definitions: Option<i64>,
+    #[serde(default)]
+    pub max_active_definitions: Option<i64>,
+    // Voc shorts, a word has a definition with any of them
+    #[serde(default)]
+    pub definition_vocs: Vec<String>,
+    // In letters
+    #[serde(default)]
+    pub min_body_length: Option<i32>,
+    #[serde(default)]
+    pub max_body_length: Option<i32>,
+    // Times the word was shown in games, max 0 gives never played words
+    #[serde(default)]
+    pub min_plays: Option<i64>,
+    #[serde(default)]
+    pub max_plays: Option<i64>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct SearchRequest {
+    #[serde(flatten)]
+    pub filter: WordFilter,
+    // Applied in turn, the id comes last
+    #[serde(default)]
+    pub field_orders: Vec<FieldOrder>,
+    #[serde(default)]
+    pub skip: i64,
+    pub take: i64,
+    // `next_cursor` of the previous page, `skip` is not used with it
+    #[serde(default)]
+    pub cursor: Option<WordCursor>,
+}
+
+// Saving under an existing name replaces that search
+#[derive(Deserialize, Debug)]
+pub struct SaveSearchRequest {
+    pub name: String,
+    #[serde(flatten)]
+    pub filter: WordFilter,
+    #[serde(default)]
+    pub field_orders: Vec<FieldOrder>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct RunSavedSearchRequest {
+    #[serde(default)]
     pub skip: i64,
     pub take: i64,
+    #[serde(default)]
+    pub cursor: Option<WordCursor>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct PreviewDefinitionsRequest {
+    pub id: i32,
+    #[serde(default)]
+    pub source: DefinitionSourceKind,
 }
 
 #[derive(Deserialize, Debug)]
 pub struct LoadDefinitionRequest {
     pub id: i32,
     pub timestamp: i64,
+    #[serde(default)]
+    pub source: DefinitionSourceKind,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct BulkLoadDefinitionsRequest {
+    #[serde(flatten)]
+    pub filter: WordFilter,
+    #[serde(default)]
+    pub source: DefinitionSourceKind,
+    pub requests_per_second: u32,
+    pub concurrency: u32,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct QueueLoadDefinitionsRequest {
+    #[serde(flatten)]
+    pub filter: WordFilter,
+    #[serde(default)]
+    pub source: DefinitionSourceKind,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ResolveUpstreamChangesRequest {
+    pub id: i32,
+    pub timestamp: i64,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct RevertWordRequest {
+    pub id: i32,
+    pub revision_id: i32,
+    pub timestamp: i64,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ReparseRequest {
+    #[serde(flatten)]
+    pub filter: WordFilter,
+    #[serde(default)]
+    pub source: Option<DefinitionSourceKind>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct JobSearchRequest {
+    #[serde(default)]
+    pub statuses: Vec<JobStatus>,
+    #[serde(default)]
+    pub kinds: Vec<JobKind>,
+    pub skip: i64,
+    pub take: i64,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CreateWordRequest {
+    pub body: String,
+    pub status: WordStatus,
+    pub difficulty: i32,
+    #[serde(default)]
+    pub part_of_speech: Option<PartOfSpeech>,
+    #[serde(default)]
+    pub gender: Option<Gender>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ImportWordsRequest {
+    pub format: ImportFormat,
+    pub content: String,
+    // Defaults for rows without their own values, tags are added to the row ones
+    #[serde(default)]
+    pub status: Option<WordStatus>,
+    #[serde(default)]
+    pub difficulty: Option<i32>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    // Queues definition loading of the new words when set
+    #[serde(default)]
+    pub source: Option<DefinitionSourceKind>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ExportWordsRequest {
+    #[serde(flatten)]
+    pub filter: WordFilter,
+    pub format: ExportFormat,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct WordTimestampDTO {
+    pub id: i32,
+    pub timestamp: i64,
+}
+
+// Words are picked either by ids, with the timestamps they were seen with, or by a filter
+#[derive(Deserialize, Debug)]
+pub struct BulkEditWordsRequest {
+    #[serde(default)]
+    pub words: Vec<WordTimestampDTO>,
+    #[serde(default)]
+    pub filter: Option<WordFilter>,
+    pub action: BulkEditAction,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ArchiveWordRequest {
+    pub id: i32,
+    pub timestamp: i64,
 }
 
 #[derive(Deserialize, Debug)]   
@@ -30,6 +226,11 @@ pub struct UpdateWordRequest {
     pub id: i32,
     pub status: WordStatus,
     pub difficulty: i32,
+    // Left out keeps the current value, null clears it
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub part_of_speech: Option<Option<PartOfSpeech>>,
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub gender: Option<Option<Gender>>,
     pub timestamp: i64,
     pub definitions: Vec<WordDefinitionDTO>
 }
@@ -43,6 +244,17 @@ pub struct CreateGameRequest {
     pub word_count: i32
 }
 
+#[derive(Deserialize, Debug)]
+pub struct GameWordsRequest {
+    pub word_count: i32,
+    #[serde(default)]
+    pub difficulties: Vec<i32>,
+    #[serde(default)]
+    pub parts_of_speech: Vec<PartOfSpeech>,
+    #[serde(default)]
+    pub genders: Vec<Gender>,
+}
+
 #[derive(Deserialize, Debug)]
 pub struct WordResultsDTO {
     pub result: bool,
@@ -1,7 +1,9 @@
 use chrono::NaiveDateTime;
 use serde::{Serialize};
 
-use crate::{domain::enums::{WordDefinitionStatus, WordLoadStatus, WordStatus}};
+use crate::domain::{enums::{BulkLoadState, DefinitionSourceKind, Gender, JobKind, JobStatus, LoadFailKind, PartOfSpeech, WordDefinitionStatus, WordLoadStatus, WordRelationKind, WordRevisionAction, WordStatus}, word_revision::WordSnapshot};
+use crate::requests::WordFilter;
+use crate::services::word_service::{FieldOrder, WordCursor};
 
 #[derive(Serialize, Clone)]
 pub struct TeamView {
@@ -23,6 +25,37 @@ pub struct WordDefinitionView {
     pub status: WordDefinitionStatus,
     pub order: i32,
     pub voc: Option<VocView>,
+    pub source: Option<DefinitionSourceKind>,
+    pub examples: Vec<String>,
+    pub is_vanished: bool,
+}
+
+#[derive(Serialize, Clone)]
+pub struct DefinitionChangeView {
+    pub current: WordDefinitionView,
+    pub proposed: WordDefinitionView,
+    pub is_text_changed: bool,
+    pub is_voc_changed: bool,
+    // Was flagged as vanished and is on the page again
+    pub is_restored: bool,
+}
+
+// Loaded definitions have id -1
+#[derive(Serialize, Clone)]
+pub struct DefinitionsPreviewView {
+    pub load_status: WordLoadStatus,
+    pub load_fail_reason: Option<String>,
+    pub current: Vec<WordDefinitionView>,
+    pub proposed: Vec<WordDefinitionView>,
+    pub added: Vec<WordDefinitionView>,
+    pub removed: Vec<WordDefinitionView>,
+    pub changed: Vec<DefinitionChangeView>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct WordRelationView {
+    pub kind: WordRelationKind,
+    pub body: String,
 }
 
 #[derive(Serialize, Clone)]
@@ -32,9 +65,133 @@ pub struct WordView {
     pub status: WordStatus,
     pub is_edited_after_load: bool,
     pub load_status: WordLoadStatus,
+    pub load_fail_reason: Option<String>,
+    pub load_fail_kind: Option<LoadFailKind>,
+    pub load_fail_http_status: Option<i32>,
+    pub load_fail_stage: Option<String>,
+    pub load_fail_attempts: i32,
+    pub synced_at: Option<NaiveDateTime>,
+    pub has_upstream_changes: bool,
+    pub tags: Vec<String>,
+    pub part_of_speech: Option<PartOfSpeech>,
+    pub gender: Option<Gender>,
+    pub syllables: Option<String>,
     pub definitions: Vec<WordDefinitionView>,
+    pub relations: Vec<WordRelationView>,
     pub timestamp: i64,
     pub difficulty: i32,
+    // Set when searched by text, higher is closer
+    pub score: Option<f32>,
+    // Matched parts wrapped in <b></b>
+    pub highlights: Vec<String>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct FacetView<T> {
+    pub value: T,
+    pub count: i64,
+}
+
+#[derive(Serialize, Clone)]
+pub struct WordFacetsView {
+    pub statuses: Vec<FacetView<WordStatus>>,
+    pub load_statuses: Vec<FacetView<WordLoadStatus>>,
+    pub difficulties: Vec<FacetView<i32>>,
+}
+
+// `next_cursor` is missing on the last page
+#[derive(Serialize, Clone)]
+pub struct WordSearchView {
+    pub words: Vec<WordView>,
+    pub total: i64,
+    pub facets: WordFacetsView,
+    pub next_cursor: Option<WordCursor>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct SavedSearchView {
+    pub id: i32,
+    pub name: String,
+    pub filter: WordFilter,
+    pub field_orders: Vec<FieldOrder>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Serialize, Clone)]
+pub struct BulkLoadView {
+    pub state: BulkLoadState,
+    pub source: DefinitionSourceKind,
+    pub total: i32,
+    pub processed: i32,
+    pub loaded: i32,
+    pub failed: i32,
+    pub last_errors: Vec<String>,
+    pub started_at: NaiveDateTime,
+    pub finished_at: Option<NaiveDateTime>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct ReparseView {
+    pub total: i32,
+    pub reparsed: i32,
+    pub failed: i32,
+    pub not_cached: i32,
+    // Bodies of words edited after the reparse has read them, they are left as they are
+    pub conflicts: Vec<String>,
+    // Words that couldn't be saved, as "body: error"
+    pub errors: Vec<String>,
+}
+
+// `duplicates` are normalised bodies that were already there or repeated in the list
+#[derive(Serialize, Clone)]
+pub struct WordImportView {
+    pub imported: i32,
+    pub queued: i32,
+    pub duplicates: Vec<String>,
+}
+
+// `timestamp` is the new one of an updated word
+#[derive(Serialize, Clone)]
+pub struct BulkEditOutcomeView {
+    pub id: i32,
+    pub is_updated: bool,
+    pub timestamp: Option<i64>,
+    pub error_code: Option<i32>,
+    pub error: Option<String>,
+}
+
+// `before` is missing for a created word
+#[derive(Serialize, Clone)]
+pub struct WordRevisionView {
+    pub id: i32,
+    pub user_id: Option<i32>,
+    pub action: WordRevisionAction,
+    pub before: Option<WordSnapshot>,
+    pub after: WordSnapshot,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Serialize, Clone)]
+pub struct JobErrorView {
+    pub attempt: i32,
+    pub error: String,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Serialize, Clone)]
+pub struct JobView {
+    pub id: i32,
+    pub kind: JobKind,
+    pub payload: String,
+    pub status: JobStatus,
+    pub attempts: i32,
+    pub max_attempts: i32,
+    pub run_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+    pub finished_at: Option<NaiveDateTime>,
+    pub last_error: Option<String>,
+    pub errors: Vec<JobErrorView>,
 }
 
 #[derive(Serialize, Clone)]
@@ -48,7 +205,8 @@ pub struct UserView {
 pub struct StatusStats {
     pub active: i64,
     pub draft: i64,
-    pub not_active: i64
+    pub not_active: i64,
+    pub archived: i64
 }
 
 #[derive(Serialize)]
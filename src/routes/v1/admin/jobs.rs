@@ -0,0 +1,28 @@
+use rocket::serde::json::Json;
+use rocket::State;
+use sqlx::PgPool;
+
+use crate::auth::Admin;
+use crate::error::VortoResult;
+use crate::requests::JobSearchRequest;
+use crate::responses::JobView;
+use crate::services::*;
+
+#[post("/jobs/search", data = "<req>")]
+pub async fn search(
+    req: Json<JobSearchRequest>,
+    _admin: Admin,
+    pool: &State<PgPool>,
+) -> VortoResult<Vec<JobView>> {
+    job_service::search(&req, pool).await
+}
+
+#[put("/jobs/<id>/retry")]
+pub async fn retry(id: i32, _admin: Admin, pool: &State<PgPool>) -> VortoResult<JobView> {
+    job_service::retry(id, pool).await
+}
+
+#[put("/jobs/<id>/cancel")]
+pub async fn cancel(id: i32, _admin: Admin, pool: &State<PgPool>) -> VortoResult<JobView> {
+    job_service::cancel(id, pool).await
+}
@@ -1,2 +1,3 @@
 pub mod users;
-pub mod words;
\ No newline at end of file
+pub mod words;pub mod jobs;
+pub mod searches;
\ No newline at end of file
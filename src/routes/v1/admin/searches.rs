@@ -0,0 +1,38 @@
+use rocket::serde::json::Json;
+use rocket::State;
+use sqlx::PgPool;
+
+use crate::auth::Admin;
+use crate::error::VortoResult;
+use crate::requests::{RunSavedSearchRequest, SaveSearchRequest};
+use crate::responses::{SavedSearchView, WordSearchView};
+use crate::services::*;
+
+#[get("/searches")]
+pub async fn get_all(admin: Admin, pool: &State<PgPool>) -> VortoResult<Vec<SavedSearchView>> {
+    saved_search_service::get_all(admin.user_id, pool).await
+}
+
+#[post("/searches", data = "<req>")]
+pub async fn save(
+    req: Json<SaveSearchRequest>,
+    admin: Admin,
+    pool: &State<PgPool>,
+) -> VortoResult<i32> {
+    saved_search_service::save(&req, admin.user_id, pool).await
+}
+
+#[delete("/searches/<id>")]
+pub async fn delete(id: i32, admin: Admin, pool: &State<PgPool>) -> VortoResult<()> {
+    saved_search_service::delete(id, admin.user_id, pool).await
+}
+
+#[post("/searches/<id>/run", data = "<req>")]
+pub async fn run(
+    id: i32,
+    req: Json<RunSavedSearchRequest>,
+    admin: Admin,
+    pool: &State<PgPool>,
+) -> VortoResult<WordSearchView> {
+    saved_search_service::run(id, &req, admin.user_id, pool).await
+}
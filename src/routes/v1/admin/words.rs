@@ -1,11 +1,25 @@
+use rocket::futures::Stream;
+use rocket::http::ContentType;
+use rocket::response::stream::TextStream;
 use rocket::serde::json::Json;
 use rocket::State;
 use sqlx::PgPool;
 
 use crate::auth::Admin;
+use crate::domain::enums::ExportFormat;
 use crate::error::VortoResult;
-use crate::requests::{LoadDefinitionRequest, SearchRequest, UpdateWordRequest};
-use crate::responses::{WordStats, WordView};
+use crate::requests::{
+    ArchiveWordRequest, BulkEditWordsRequest, BulkLoadDefinitionsRequest, CreateWordRequest,
+    ExportWordsRequest, ImportWordsRequest, LoadDefinitionRequest, PreviewDefinitionsRequest,
+    QueueLoadDefinitionsRequest, ReparseRequest, ResolveUpstreamChangesRequest, RevertWordRequest,
+    SearchRequest, UpdateWordRequest,
+};
+use crate::responses::{
+    BulkEditOutcomeView, BulkLoadView, DefinitionsPreviewView, ReparseView, WordImportView,
+    WordRevisionView, WordSearchView, WordStats,
+};
+use crate::services::definition_source::DefinitionSources;
+use crate::services::http_client::HttpClient;
 use crate::services::*;
 
 #[post("/words/search", data = "<req>")]
@@ -13,36 +27,180 @@ pub async fn search(
     req: Json<SearchRequest>,
     _admin: Admin,
     pool: &State<PgPool>,
-) -> VortoResult<Vec<WordView>> {
-    word_service::search(
-        &req.text,
-        &req.statuses,
-        &req.load_statuses,
-        &req.difficulties,
-        &req.field_order,
-        req.skip,
-        req.take,
-        pool.inner(),
-    )
-    .await
+) -> VortoResult<WordSearchView> {
+    word_service::search_page(&req, pool).await
 }
 
 #[put("/words/load_definitions", data = "<req>")]
 pub async fn load_definitions(
     req: Json<LoadDefinitionRequest>,
+    admin: Admin,
+    pool: &State<PgPool>,
+    sources: &State<DefinitionSources>,
+    client: &State<HttpClient>,
+) -> VortoResult<()> {
+    let source = sources.get(&req.source)?;
+    word_service::load_definitions(
+        req.id,
+        req.timestamp,
+        source.as_ref(),
+        client,
+        admin.user_id,
+        pool,
+    )
+    .await
+}
+
+#[post("/words/load_definitions/preview", data = "<req>")]
+pub async fn preview_definitions(
+    req: Json<PreviewDefinitionsRequest>,
+    _admin: Admin,
+    pool: &State<PgPool>,
+    sources: &State<DefinitionSources>,
+    client: &State<HttpClient>,
+) -> VortoResult<DefinitionsPreviewView> {
+    let source = sources.get(&req.source)?;
+    word_service::preview_definitions(req.id, source.as_ref(), client, pool).await
+}
+
+#[post("/words/bulk_load", data = "<req>")]
+pub async fn bulk_load(
+    req: Json<BulkLoadDefinitionsRequest>,
+    _admin: Admin,
+    pool: &State<PgPool>,
+    sources: &State<DefinitionSources>,
+) -> VortoResult<BulkLoadView> {
+    bulk_load_service::start(req.into_inner(), sources, pool).await
+}
+
+#[get("/words/bulk_load")]
+pub async fn bulk_load_status(
+    _admin: Admin,
+    pool: &State<PgPool>,
+) -> VortoResult<Option<BulkLoadView>> {
+    bulk_load_service::status(pool).await
+}
+
+#[delete("/words/bulk_load")]
+pub async fn cancel_bulk_load(
     _admin: Admin,
     pool: &State<PgPool>,
+) -> VortoResult<BulkLoadView> {
+    bulk_load_service::cancel(pool).await
+}
+
+#[post("/words/load_definitions/queue", data = "<req>")]
+pub async fn queue_load_definitions(
+    req: Json<QueueLoadDefinitionsRequest>,
+    _admin: Admin,
+    pool: &State<PgPool>,
+    sources: &State<DefinitionSources>,
+) -> VortoResult<i32> {
+    job_service::queue_load_definitions(req.into_inner(), sources, pool).await
+}
+
+#[post("/words/reparse", data = "<req>")]
+pub async fn reparse(
+    req: Json<ReparseRequest>,
+    _admin: Admin,
+    pool: &State<PgPool>,
+) -> VortoResult<ReparseView> {
+    raw_page_service::reparse(&req, pool).await
+}
+
+#[post("/words/sync")]
+pub async fn sync(_admin: Admin, pool: &State<PgPool>) -> VortoResult<i32> {
+    sync_service::schedule(pool).await
+}
+
+#[put("/words/upstream_changes/resolve", data = "<req>")]
+pub async fn resolve_upstream_changes(
+    req: Json<ResolveUpstreamChangesRequest>,
+    admin: Admin,
+    pool: &State<PgPool>,
 ) -> VortoResult<()> {
-    word_service::load_definitions(req.id, req.timestamp, pool).await
+    word_service::resolve_upstream_changes(&req, admin.user_id, pool).await
+}
+
+#[post("/words", data = "<req>")]
+pub async fn create(
+    req: Json<CreateWordRequest>,
+    admin: Admin,
+    pool: &State<PgPool>,
+) -> VortoResult<i32> {
+    word_service::create(&req, admin.user_id, pool).await
+}
+
+#[post("/words/import", data = "<req>")]
+pub async fn import(
+    req: Json<ImportWordsRequest>,
+    admin: Admin,
+    sources: &State<DefinitionSources>,
+    pool: &State<PgPool>,
+) -> VortoResult<WordImportView> {
+    word_import_service::import(&req, admin.user_id, sources, pool).await
+}
+
+#[post("/words/export", data = "<req>")]
+pub async fn export(
+    req: Json<ExportWordsRequest>,
+    _admin: Admin,
+    pool: &State<PgPool>,
+) -> (ContentType, TextStream<impl Stream<Item = String>>) {
+    let content_type = match req.format {
+        ExportFormat::Csv => ContentType::CSV,
+        ExportFormat::JsonLines => ContentType::new("application", "x-ndjson"),
+    };
+
+    (
+        content_type,
+        TextStream(word_export_service::export(req.into_inner(), pool.inner().clone())),
+    )
+}
+
+#[delete("/words", data = "<req>")]
+pub async fn archive(
+    req: Json<ArchiveWordRequest>,
+    admin: Admin,
+    pool: &State<PgPool>,
+) -> VortoResult<()> {
+    word_service::archive(&req, admin.user_id, pool).await
 }
 
 #[put("/words", data = "<req>")]
 pub async fn update(
     req: Json<UpdateWordRequest>,
+    admin: Admin,
+    pool: &State<PgPool>,
+) -> VortoResult<()> {
+    word_service::update(req.into_inner(), admin.user_id, pool).await
+}
+
+#[put("/words/bulk", data = "<req>")]
+pub async fn bulk_edit(
+    req: Json<BulkEditWordsRequest>,
+    admin: Admin,
+    pool: &State<PgPool>,
+) -> VortoResult<Vec<BulkEditOutcomeView>> {
+    word_service::bulk_edit(&req, admin.user_id, pool).await
+}
+
+#[get("/words/<id>/history")]
+pub async fn history(
+    id: i32,
     _admin: Admin,
     pool: &State<PgPool>,
+) -> VortoResult<Vec<WordRevisionView>> {
+    word_service::history(id, pool).await
+}
+
+#[put("/words/revert", data = "<req>")]
+pub async fn revert(
+    req: Json<RevertWordRequest>,
+    admin: Admin,
+    pool: &State<PgPool>,
 ) -> VortoResult<()> {
-    word_service::update(req.into_inner(), pool).await
+    word_service::revert(&req, admin.user_id, pool).await
 }
 
 #[get("/words/stats")]
@@ -1,6 +1,6 @@
 use crate::error::VortoResult;
-use crate::requests::{CompleteRoundRequest, CreateGameRequest};
-use crate::responses::GameView;
+use crate::requests::{CompleteRoundRequest, CreateGameRequest, GameWordsRequest};
+use crate::responses::{GameView, GameWordView};
 use crate::services::*;
 use rocket::serde::json::Json;
 use rocket::State;
@@ -16,6 +16,11 @@ pub async fn complete_round(req: Json<CompleteRoundRequest>, pool: &State<PgPool
     game_service::complete_round(req.into_inner(), pool).await
 }
 
+#[post("/games/words", data = "<req>")]
+pub async fn words(req: Json<GameWordsRequest>, pool: &State<PgPool>) -> VortoResult<Vec<GameWordView>> {
+    game_service::words(req.into_inner(), pool).await
+}
+
 #[get("/games/<id>")]
 pub async fn game_view(id: i32, pool: &State<PgPool>) -> VortoResult<GameView> {
     game_service::game_view(id, pool).await
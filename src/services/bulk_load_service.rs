@@ -0,0 +1,152 @@
+use std::str::FromStr;
+
+use chrono::Utc;
+use sqlx::{PgPool, Postgres, Transaction};
+
+use crate::{
+    db,
+    domain::{
+        self,
+        bulk_load::BulkLoad,
+        enums::{BulkLoadState, DefinitionSourceKind, JobKind, WordLoadStatus},
+        job::Job,
+    },
+    error::{VortoError, VortoErrorCode, VortoResult},
+    requests::BulkLoadDefinitionsRequest,
+    responses::BulkLoadView,
+    services::{
+        definition_source::DefinitionSources,
+        job_service::{self, LoadDefinitionsPayload},
+        word_service,
+    },
+};
+
+fn bulk_load_view(bulk_load: &BulkLoad) -> VortoResult<BulkLoadView> {
+    VortoResult::Ok(BulkLoadView {
+        state: BulkLoadState::from_str(&bulk_load.state).ok()?,
+        source: DefinitionSourceKind::from_str(&bulk_load.source).ok()?,
+        total: bulk_load.total,
+        processed: bulk_load.processed,
+        loaded: bulk_load.loaded,
+        failed: bulk_load.failed,
+        last_errors: bulk_load.last_errors.clone(),
+        started_at: bulk_load.started_at,
+        finished_at: bulk_load.finished_at,
+    })
+}
+
+// Every word is loaded by its own job, the jobs are spread over time by the rate limit
+pub async fn start(
+    req: BulkLoadDefinitionsRequest,
+    sources: &DefinitionSources,
+    pool: &PgPool,
+) -> VortoResult<BulkLoadView> {
+    sources.get(&req.source)?;
+    let words = word_service::filter_words(&req.filter, pool).await?;
+
+    let mut tx = pool.begin().await?;
+    // Two bulk loads started at once would both pass the check below
+    sqlx::query("LOCK TABLE bulk_loads IN EXCLUSIVE MODE")
+        .execute(&mut tx)
+        .await?;
+    domain::bulk_load::validate_not_running(
+        db::bulk_load::get_last(pool, Some(&mut tx)).await?.as_ref(),
+    )?;
+    let mut bulk_load = domain::bulk_load::new(
+        &req.source,
+        words.len() as i32,
+        req.requests_per_second,
+        req.concurrency,
+        &Utc::now(),
+    )?;
+    bulk_load.id = db::bulk_load::insert(&bulk_load, pool, Some(&mut tx)).await?;
+    for (index, word) in words.iter().enumerate() {
+        let payload = LoadDefinitionsPayload {
+            word_id: word.id,
+            source: req.source,
+        };
+        job_service::enqueue_for_bulk_load(
+            &JobKind::LoadDefinitions,
+            &payload,
+            bulk_load.id,
+            domain::bulk_load::run_at(&bulk_load, index),
+            pool,
+            Some(&mut tx),
+        )
+        .await?;
+    }
+    tx.commit().await?;
+
+    bulk_load_view(&bulk_load)
+}
+
+pub async fn status(pool: &PgPool) -> VortoResult<Option<BulkLoadView>> {
+    match db::bulk_load::get_last(pool, None).await? {
+        Some(bulk_load) => VortoResult::Ok(Some(bulk_load_view(&bulk_load)?)),
+        None => VortoResult::Ok(None),
+    }
+}
+
+// Words which are being loaded right now are finished, jobs of the rest are cancelled
+pub async fn cancel(pool: &PgPool) -> VortoResult<BulkLoadView> {
+    let bulk_load = match db::bulk_load::get_last(pool, None).await? {
+        Some(bulk_load) => bulk_load,
+        None => {
+            return VortoResult::Err(VortoError::new(
+                VortoErrorCode::Validation,
+                "Bulk load is not running".to_owned(),
+            ))
+        }
+    };
+    let now = Utc::now();
+
+    let mut tx = pool.begin().await?;
+    // Jobs first, workers lock a job before its bulk load
+    db::job::cancel_pending_by_bulk_load_id(bulk_load.id, now.naive_utc(), &mut tx).await?;
+    let bulk_load = db::bulk_load::lock_by_id(bulk_load.id, &mut tx).await?;
+    let bulk_load = domain::bulk_load::cancel(&bulk_load, &now)?;
+    db::bulk_load::update(&bulk_load, pool, Some(&mut tx)).await?;
+    tx.commit().await?;
+
+    bulk_load_view(&bulk_load)
+}
+
+// Locks the bulk load until `tx` ends
+pub async fn has_free_slot(
+    bulk_load_id: i32,
+    tx: &mut Transaction<'_, Postgres>,
+) -> VortoResult<bool> {
+    let bulk_load = db::bulk_load::lock_by_id(bulk_load_id, tx).await?;
+    let running = db::job::count_running_by_bulk_load_id(bulk_load_id, tx).await?;
+
+    VortoResult::Ok(domain::bulk_load::has_free_slot(&bulk_load, running))
+}
+
+// Counts the job once it is over for good, `error` is set when it has failed or was cancelled.
+// A completed job could still leave its word without definitions, that is a failure too
+pub async fn add_result(
+    job: &Job,
+    error: Option<&str>,
+    pool: &PgPool,
+    tx: &mut Transaction<'_, Postgres>,
+) -> VortoResult<()> {
+    let bulk_load_id = match job.bulk_load_id {
+        Some(id) => id,
+        None => return VortoResult::Ok(()),
+    };
+    let payload: LoadDefinitionsPayload = serde_json::from_str(&job.payload)?;
+    let word = db::word::get_by_id(payload.word_id, pool).await?;
+    let result = match error {
+        Some(e) => Err(e.to_owned()),
+        None if word.load_status == WordLoadStatus::Loaded.to_string() => Ok(()),
+        None => Err(word
+            .load_fail_reason
+            .unwrap_or_else(|| "Definitions are not loaded".to_owned())),
+    };
+
+    let bulk_load = db::bulk_load::lock_by_id(bulk_load_id, tx).await?;
+    let bulk_load = domain::bulk_load::add_result(&bulk_load, &word.body, result, &Utc::now());
+    db::bulk_load::update(&bulk_load, pool, Some(tx)).await?;
+
+    VortoResult::Ok(())
+}
@@ -0,0 +1,181 @@
+use std::{collections::HashMap, env, sync::Arc};
+
+use crate::{
+    domain::{
+        enums::DefinitionSourceKind,
+        word::{ParsedDefinition, ParsedPage},
+    },
+    error::{ParseError, VortoError, VortoErrorCode, VortoResult},
+    services::{http_client::HttpClient, wiki_parser_service, wikitext_parser_service},
+};
+
+const LOCAL_DEFINITIONS_PATH: &str = "LOCAL_DEFINITIONS_PATH";
+const WIKTIONARY_URL: &str = "WIKTIONARY_URL";
+const EN_WIKTIONARY_URL: &str = "EN_WIKTIONARY_URL";
+const WIKTIONARY_API_URL: &str = "WIKTIONARY_API_URL";
+const DEFAULT_WIKTIONARY_URL: &str = "https://ru.wiktionary.org";
+const DEFAULT_EN_WIKTIONARY_URL: &str = "https://en.wiktionary.org";
+
+#[rocket::async_trait]
+pub trait DefinitionSource: Send + Sync {
+    fn kind(&self) -> DefinitionSourceKind;
+
+    // Page source as it came, it goes to the raw page cache before parsing.
+    // Web sources go through the shared client, so they are all under its rate cap
+    async fn fetch(&self, client: &HttpClient, word: &str) -> Result<String, ParseError>;
+
+    fn parse(&self, content: &str) -> Result<ParsedPage, ParseError> {
+        parse_raw(&self.kind(), content)
+    }
+}
+
+// Cached pages are parsed by the kind of source they came from, no need for the source itself
+pub fn parse_raw(kind: &DefinitionSourceKind, content: &str) -> Result<ParsedPage, ParseError> {
+    match kind {
+        DefinitionSourceKind::Wiktionary => wiki_parser_service::parse_html(content),
+        DefinitionSourceKind::EnWiktionary => wiki_parser_service::parse_en_html(content),
+        DefinitionSourceKind::WiktionaryApi | DefinitionSourceKind::WiktionaryDump => {
+            wikitext_parser_service::parse_wikitext(content)
+        }
+        DefinitionSourceKind::LocalFile => {
+            let definitions: Vec<ParsedDefinition> = serde_json::from_str(content)?;
+            Ok(ParsedPage {
+                definitions,
+                ..Default::default()
+            })
+        }
+    }
+}
+
+// Russian and English Wiktionary share the page URLs, their HTML is parsed by `kind`
+pub struct WiktionarySource {
+    kind: DefinitionSourceKind,
+    base_url: String,
+}
+
+impl WiktionarySource {
+    pub fn new(kind: DefinitionSourceKind, base_url: &str) -> Self {
+        Self {
+            kind,
+            base_url: base_url.to_owned(),
+        }
+    }
+}
+
+#[rocket::async_trait]
+impl DefinitionSource for WiktionarySource {
+    fn kind(&self) -> DefinitionSourceKind {
+        self.kind
+    }
+
+    async fn fetch(&self, client: &HttpClient, word: &str) -> Result<String, ParseError> {
+        wiki_parser_service::fetch_html(client, &self.base_url, word).await
+    }
+}
+
+// Russian Wiktionary through the MediaWiki API, parses wikitext templates instead of the skin's HTML
+pub struct WiktionaryApiSource {
+    base_url: String,
+}
+
+impl WiktionaryApiSource {
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            base_url: base_url.to_owned(),
+        }
+    }
+}
+
+#[rocket::async_trait]
+impl DefinitionSource for WiktionaryApiSource {
+    fn kind(&self) -> DefinitionSourceKind {
+        DefinitionSourceKind::WiktionaryApi
+    }
+
+    async fn fetch(&self, client: &HttpClient, word: &str) -> Result<String, ParseError> {
+        wikitext_parser_service::fetch_wikitext(client, &self.base_url, word).await
+    }
+}
+
+// JSON file in a form of { "word": [[["voc short"], "definition", ["example"]]] },
+// examples can be left out. Definitions of a word are cached as a JSON array
+pub struct LocalFileSource {
+    path: String,
+}
+
+impl LocalFileSource {
+    pub fn new(path: &str) -> Self {
+        Self {
+            path: path.to_owned(),
+        }
+    }
+}
+
+#[rocket::async_trait]
+impl DefinitionSource for LocalFileSource {
+    fn kind(&self) -> DefinitionSourceKind {
+        DefinitionSourceKind::LocalFile
+    }
+
+    async fn fetch(&self, _client: &HttpClient, word: &str) -> Result<String, ParseError> {
+        let content = tokio::fs::read_to_string(&self.path).await?;
+        let mut words: HashMap<String, serde_json::Value> = serde_json::from_str(&content)?;
+
+        let definitions = words.remove(word).ok_or(ParseError::PageNotFound)?;
+        Ok(definitions.to_string())
+    }
+}
+
+#[derive(Clone)]
+pub struct DefinitionSources {
+    sources: HashMap<DefinitionSourceKind, Arc<dyn DefinitionSource>>,
+}
+
+impl DefinitionSources {
+    // Base URLs can be pointed to a local stand-in server
+    pub fn from_env() -> Self {
+        let url = |name: &str, default: &str| env::var(name).unwrap_or_else(|_| default.to_owned());
+
+        let mut sources: HashMap<DefinitionSourceKind, Arc<dyn DefinitionSource>> = HashMap::new();
+        sources.insert(
+            DefinitionSourceKind::Wiktionary,
+            Arc::new(WiktionarySource::new(
+                DefinitionSourceKind::Wiktionary,
+                &url(WIKTIONARY_URL, DEFAULT_WIKTIONARY_URL),
+            )),
+        );
+        sources.insert(
+            DefinitionSourceKind::EnWiktionary,
+            Arc::new(WiktionarySource::new(
+                DefinitionSourceKind::EnWiktionary,
+                &url(EN_WIKTIONARY_URL, DEFAULT_EN_WIKTIONARY_URL),
+            )),
+        );
+        sources.insert(
+            DefinitionSourceKind::WiktionaryApi,
+            Arc::new(WiktionaryApiSource::new(&url(
+                WIKTIONARY_API_URL,
+                DEFAULT_WIKTIONARY_URL,
+            ))),
+        );
+
+        if let Ok(path) = env::var(LOCAL_DEFINITIONS_PATH) {
+            sources.insert(
+                DefinitionSourceKind::LocalFile,
+                Arc::new(LocalFileSource::new(&path)),
+            );
+        }
+
+        Self { sources }
+    }
+
+    pub fn get(&self, kind: &DefinitionSourceKind) -> VortoResult<Arc<dyn DefinitionSource>> {
+        match self.sources.get(kind) {
+            Some(source) => VortoResult::Ok(source.clone()),
+            None => VortoResult::Err(VortoError::new(
+                VortoErrorCode::Validation,
+                format!("Definition source '{}' is not configured", kind),
+            )),
+        }
+    }
+}
@@ -0,0 +1,257 @@
+use sqlx::PgPool;
+use tokio::{
+    fs::File,
+    io::{AsyncBufReadExt, BufReader},
+};
+
+use crate::{
+    common::vec_to_map,
+    db,
+    domain::enums::{DefinitionSourceKind, WordRevisionAction},
+    error::VortoResult,
+    services::{raw_page_service, word_service, wikitext_parser_service},
+};
+
+const MAIN_NAMESPACE: &str = "0";
+
+#[derive(Debug, Default)]
+pub struct DumpImportStats {
+    pub pages: usize,
+    pub matched: usize,
+    pub loaded: usize,
+    pub failed: usize,
+}
+
+fn unescape_xml(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#039;", "'")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+fn tag_value<'r>(line: &'r str, tag: &str) -> Option<&'r str> {
+    let open = format!("<{}>", tag);
+    let close = format!("</{}>", tag);
+    let start = line.find(&open)? + open.len();
+    let end = line[start..].find(&close)? + start;
+    Some(&line[start..end])
+}
+
+#[derive(Default)]
+struct DumpPage {
+    title: String,
+    ns: String,
+    text: Option<String>,
+}
+
+// Goes through a dump line by line, the text of a page is kept only when it is wanted
+#[derive(Default)]
+struct DumpReader {
+    page: DumpPage,
+    in_text: bool,
+}
+
+impl DumpReader {
+    // Gives the page back once its closing tag is read, with the text unescaped
+    fn read_line(&mut self, line: &str, is_wanted: impl Fn(&DumpPage) -> bool) -> Option<DumpPage> {
+        if self.in_text {
+            let text = self.page.text.get_or_insert_with(String::new);
+            if let Some(end) = line.find("</text>") {
+                text.push_str(&line[..end]);
+                self.in_text = false;
+            } else {
+                text.push_str(line);
+                text.push('\n');
+            }
+            return None;
+        }
+
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("<page>") {
+            self.page = DumpPage::default();
+        } else if let Some(title) = tag_value(trimmed, "title") {
+            self.page.title = unescape_xml(title);
+        } else if let Some(ns) = tag_value(trimmed, "ns") {
+            self.page.ns = ns.to_owned();
+        } else if trimmed.starts_with("<text") && !trimmed.ends_with("/>") {
+            if !is_wanted(&self.page) {
+                return None;
+            }
+            let content = &trimmed[trimmed.find('>').map(|p| p + 1).unwrap_or(0)..];
+            if let Some(end) = content.find("</text>") {
+                self.page.text = Some(content[..end].to_owned());
+            } else {
+                self.page.text = Some(format!("{}\n", content));
+                self.in_text = true;
+            }
+        } else if trimmed.starts_with("</page>") {
+            let mut page = std::mem::take(&mut self.page);
+            page.text = page.text.map(|t| unescape_xml(&t));
+            return Some(page);
+        }
+
+        None
+    }
+}
+
+// Streams a pages-articles XML dump, the text of a page is kept in memory
+// only when its title is one of the words
+pub async fn import(path: &str, pool: &PgPool) -> VortoResult<DumpImportStats> {
+    let words = db::word::get_all(pool).await?;
+    let mut words_by_body = vec_to_map(&words, |w| w.body.clone(), |w| w.clone());
+
+    let mut stats = DumpImportStats::default();
+    let mut reader = DumpReader::default();
+    let mut lines = BufReader::new(File::open(path).await?).lines();
+
+    while let Some(line) = lines.next_line().await? {
+        let page = match reader.read_line(&line, |p| {
+            p.ns == MAIN_NAMESPACE && words_by_body.contains_key(&p.title)
+        }) {
+            Some(page) => page,
+            None => continue,
+        };
+
+        stats.pages += 1;
+        let (text, word) = match (page.text, words_by_body.remove(&page.title)) {
+            (Some(text), Some(word)) => (text, word),
+            _ => continue,
+        };
+        stats.matched += 1;
+
+        let page_result = wikitext_parser_service::parse_wikitext(&text);
+        let has_definitions = matches!(&page_result, Ok(p) if !p.definitions.is_empty());
+
+        let saved = match raw_page_service::cache(
+            word.id,
+            &DefinitionSourceKind::WiktionaryDump,
+            &text,
+            pool,
+        )
+        .await
+        {
+            VortoResult::Ok(_) => {
+                word_service::save_loaded_definitions(
+                    &word,
+                    word.timestamp,
+                    &page_result,
+                    &DefinitionSourceKind::WiktionaryDump,
+                    None,
+                    WordRevisionAction::Load,
+                    pool,
+                )
+                .await
+            }
+            VortoResult::Err(e) => VortoResult::Err(e),
+        };
+
+        match saved {
+            VortoResult::Ok(_) if has_definitions => stats.loaded += 1,
+            VortoResult::Ok(_) => stats.failed += 1,
+            VortoResult::Err(e) => {
+                error!("Dump import of '{}' failed: {}", word.body, e.message);
+                stats.failed += 1;
+            }
+        }
+
+        if stats.matched % 1000 == 0 {
+            info!("Dump import: {:?}", stats);
+        }
+    }
+
+    VortoResult::Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        enums::{Gender, PartOfSpeech},
+        word::ParsedDefinition,
+    };
+
+    fn read_sample(is_wanted: impl Fn(&DumpPage) -> bool) -> Vec<DumpPage> {
+        let content = std::fs::read_to_string(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/fixtures/wiktionary/ru/pages-articles-sample.xml"
+        ))
+        .unwrap();
+        let mut reader = DumpReader::default();
+
+        content
+            .lines()
+            .filter_map(|line| reader.read_line(line, &is_wanted))
+            .collect()
+    }
+
+    #[test]
+    fn reads_every_page_and_text_of_wanted_ones() {
+        let pages = read_sample(|p| p.ns == MAIN_NAMESPACE);
+
+        let titles: Vec<&str> = pages.iter().map(|p| p.title.as_str()).collect();
+        assert_eq!(titles, vec!["кот", "слон", "Шаблон:пример", "ёж"]);
+        assert!(pages[0].text.as_ref().unwrap().starts_with("= {{-ru-}} ="));
+        assert!(pages[1].text.is_some());
+        assert_eq!(pages[2].text, None);
+        assert_eq!(pages[3].text, None);
+    }
+
+    #[test]
+    fn unescapes_text() {
+        let pages = read_sample(|p| p.title == "кот");
+
+        let text = pages[0].text.as_ref().unwrap();
+        assert!(text.contains("<ref>{{source-ru|БАС}}</ref>"));
+        assert!(text.contains("<!-- comment -->"));
+        assert_eq!(pages[1].text, None);
+    }
+
+    #[test]
+    fn parses_definitions_of_read_pages() {
+        let pages = read_sample(|p| p.ns == MAIN_NAMESPACE);
+
+        let kot = wikitext_parser_service::parse_wikitext(pages[0].text.as_ref().unwrap()).unwrap();
+        let slon =
+            wikitext_parser_service::parse_wikitext(pages[1].text.as_ref().unwrap()).unwrap();
+
+        assert_eq!(
+            kot.definitions,
+            vec![
+                ParsedDefinition {
+                    vocs: vec!["зоол.".to_owned()],
+                    definition: "самец кошки".to_owned(),
+                    examples: vec!["Кот сидел на крыше.".to_owned()],
+                },
+                ParsedDefinition {
+                    vocs: vec!["разг.".to_owned()],
+                    definition: "то же, что кошка; домашнее животное".to_owned(),
+                    examples: vec![],
+                },
+                ParsedDefinition {
+                    vocs: vec![],
+                    definition: "то же, что морской котик".to_owned(),
+                    examples: vec![],
+                },
+            ]
+        );
+        assert_eq!(kot.part_of_speech, Some(PartOfSpeech::Noun));
+        assert_eq!(kot.gender, Some(Gender::Masculine));
+        assert_eq!(
+            slon.definitions,
+            vec![
+                ParsedDefinition {
+                    vocs: vec!["зоол.".to_owned()],
+                    definition: "крупное млекопитающее с хоботом и бивнями".to_owned(),
+                    examples: vec!["Слона-то я и не приметил.".to_owned()],
+                },
+                ParsedDefinition {
+                    vocs: vec!["перен.".to_owned()],
+                    definition: "очень большой, неуклюжий человек".to_owned(),
+                    examples: vec![],
+                },
+            ]
+        );
+    }
+}
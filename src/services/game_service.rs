@@ -5,8 +5,8 @@ use sqlx::PgPool;
 use crate::{
     db, domain,
     error::VortoResult,
-    requests::{CompleteRoundRequest, CreateGameRequest},
-    responses::GameView,
+    requests::{CompleteRoundRequest, CreateGameRequest, GameWordsRequest},
+    responses::{GameView, GameWordView},
 };
 
 pub async fn create(req: CreateGameRequest, pool: &PgPool) -> VortoResult<GameView> {
@@ -67,6 +67,29 @@ pub async fn complete_round(req: CompleteRoundRequest, pool: &PgPool) -> VortoRe
     VortoResult::Ok(game_view)
 }
 
+pub async fn words(req: GameWordsRequest, pool: &PgPool) -> VortoResult<Vec<GameWordView>> {
+    domain::game::validate_word_count(req.word_count)?;
+
+    let words = db::word::get_random_active(
+        req.word_count,
+        &req.difficulties,
+        &req.parts_of_speech,
+        &req.genders,
+        pool,
+    )
+    .await?;
+
+    VortoResult::Ok(
+        words
+            .into_iter()
+            .map(|w| GameWordView {
+                id: w.id,
+                body: w.body,
+            })
+            .collect(),
+    )
+}
+
 pub async fn game_view(id: i32, pool: &PgPool) -> VortoResult<GameView> {
     db::game::game_view(id, pool).await
 }
@@ -0,0 +1,127 @@
+use std::{sync::Arc, time::Duration};
+
+use reqwest::{header::RETRY_AFTER, Response};
+use tokio::time::{sleep, sleep_until, Instant};
+
+use crate::{common::env_or, error::ParseError};
+
+const HTTP_TIMEOUT_SECONDS: &str = "HTTP_TIMEOUT_SECONDS";
+const HTTP_CONNECT_TIMEOUT_SECONDS: &str = "HTTP_CONNECT_TIMEOUT_SECONDS";
+const HTTP_USER_AGENT: &str = "HTTP_USER_AGENT";
+const HTTP_MAX_RETRIES: &str = "HTTP_MAX_RETRIES";
+const HTTP_REQUESTS_PER_SECOND: &str = "HTTP_REQUESTS_PER_SECOND";
+
+const DEFAULT_TIMEOUT_SECONDS: u64 = 20;
+const DEFAULT_CONNECT_TIMEOUT_SECONDS: u64 = 5;
+const DEFAULT_USER_AGENT: &str = "VortoBot/0.1 (https://github.com/Mefgalm/Vorto-backend-rust)";
+const DEFAULT_MAX_RETRIES: u32 = 3;
+const DEFAULT_REQUESTS_PER_SECOND: u32 = 5;
+
+const RETRY_BASE: Duration = Duration::from_millis(500);
+const RETRY_MAX: Duration = Duration::from_secs(30);
+
+// Hands out evenly spaced time slots, so concurrent requests stay within the rate
+pub struct RateLimiter {
+    period: Duration,
+    next: tokio::sync::Mutex<Instant>,
+}
+
+impl RateLimiter {
+    pub fn new(requests_per_second: u32) -> Self {
+        Self {
+            period: Duration::from_secs(1) / requests_per_second,
+            next: tokio::sync::Mutex::new(Instant::now()),
+        }
+    }
+
+    pub async fn wait(&self) {
+        let slot = {
+            let mut next = self.next.lock().await;
+            let slot = (*next).max(Instant::now());
+            *next = slot + self.period;
+            slot
+        };
+        sleep_until(slot).await;
+    }
+}
+
+// 0.5s, 1s, 2s... but not longer than 30s
+fn backoff(attempt: u32) -> Duration {
+    (RETRY_BASE * 2u32.pow(attempt.min(10))).min(RETRY_MAX)
+}
+
+fn retry_after(response: &Response) -> Option<Duration> {
+    response
+        .headers()
+        .get(RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<u64>().ok())
+        .map(|seconds| Duration::from_secs(seconds).min(RETRY_MAX))
+}
+
+async fn read(response: Response) -> Result<String, ParseError> {
+    match response.status().as_u16() {
+        404 => Err(ParseError::PageNotFound),
+        status if status >= 400 => Err(ParseError::HttpStatus(status)),
+        _ => Ok(response.text().await?),
+    }
+}
+
+// One client for all dictionary requests, the rate cap is shared by every caller
+#[derive(Clone)]
+pub struct HttpClient {
+    client: reqwest::Client,
+    rate_limiter: Arc<RateLimiter>,
+    max_retries: u32,
+}
+
+impl HttpClient {
+    pub fn from_env() -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(env_or(
+                HTTP_TIMEOUT_SECONDS,
+                DEFAULT_TIMEOUT_SECONDS,
+            )))
+            .connect_timeout(Duration::from_secs(env_or(
+                HTTP_CONNECT_TIMEOUT_SECONDS,
+                DEFAULT_CONNECT_TIMEOUT_SECONDS,
+            )))
+            .user_agent(env_or(HTTP_USER_AGENT, DEFAULT_USER_AGENT.to_owned()))
+            .build()
+            .expect("Failed to create HTTP client");
+
+        Self {
+            client,
+            rate_limiter: Arc::new(RateLimiter::new(
+                env_or(HTTP_REQUESTS_PER_SECOND, DEFAULT_REQUESTS_PER_SECOND).max(1),
+            )),
+            max_retries: env_or(HTTP_MAX_RETRIES, DEFAULT_MAX_RETRIES),
+        }
+    }
+
+    // 429 and 5xx responses and connection failures are retried with a backoff,
+    // Retry-After of the response wins when it is given
+    pub async fn get_text(&self, url: &str) -> Result<String, ParseError> {
+        let mut attempt = 0;
+        loop {
+            self.rate_limiter.wait().await;
+            let (result, wait) = match self.client.get(url).send().await {
+                Ok(response) => {
+                    let wait = retry_after(&response);
+                    (read(response).await, wait)
+                }
+                Err(e) => (Err(ParseError::from(e)), None),
+            };
+
+            match result {
+                Err(parse_error) if parse_error.is_transient() && attempt < self.max_retries => {
+                    attempt += 1;
+                    let wait = wait.unwrap_or_else(|| backoff(attempt - 1));
+                    warn!("Retry {} of '{}' in {:?}: {}", attempt, url, wait, parse_error);
+                    sleep(wait).await;
+                }
+                result => return result,
+            }
+        }
+    }
+}
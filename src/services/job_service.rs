@@ -0,0 +1,348 @@
+use std::{collections::HashMap, str::FromStr, time::Duration};
+
+use chrono::{NaiveDateTime, Utc};
+use rocket::Orbit;
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Postgres, Transaction};
+
+use crate::{
+    common::env_or,
+    db,
+    domain::{
+        self,
+        enums::{DefinitionSourceKind, JobKind, JobStatus, WordRevisionAction},
+        job::{Job, JobError},
+    },
+    error::{VortoError, VortoErrorCode, VortoResult},
+    requests::{JobSearchRequest, QueueLoadDefinitionsRequest},
+    responses::{JobErrorView, JobView},
+    services::{
+        bulk_load_service, definition_source::DefinitionSources, http_client::HttpClient,
+        raw_page_service, sync_service, word_service,
+    },
+};
+
+const JOB_WORKERS: &str = "JOB_WORKERS";
+const DEFAULT_JOB_WORKERS: usize = 2;
+const DEFAULT_MAX_ATTEMPTS: i32 = 5;
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(60);
+// Running job without a heartbeat for that long is taken as one whose worker has died
+const STALE_LOCK_MINUTES: i64 = 5;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LoadDefinitionsPayload {
+    pub word_id: i32,
+    pub source: DefinitionSourceKind,
+}
+
+pub async fn enqueue<T: Serialize>(
+    kind: &JobKind,
+    payload: &T,
+    pool: &PgPool,
+    tx: Option<&mut Transaction<'_, Postgres>>,
+) -> VortoResult<i32> {
+    let job = domain::job::new(
+        kind,
+        &serde_json::to_string(payload)?,
+        DEFAULT_MAX_ATTEMPTS,
+        &Utc::now(),
+    )?;
+
+    db::job::insert(&job, pool, tx).await
+}
+
+pub async fn enqueue_for_bulk_load<T: Serialize>(
+    kind: &JobKind,
+    payload: &T,
+    bulk_load_id: i32,
+    run_at: NaiveDateTime,
+    pool: &PgPool,
+    tx: Option<&mut Transaction<'_, Postgres>>,
+) -> VortoResult<i32> {
+    let job = domain::job::new(
+        kind,
+        &serde_json::to_string(payload)?,
+        DEFAULT_MAX_ATTEMPTS,
+        &Utc::now(),
+    )?;
+    let job = domain::job::for_bulk_load(&job, bulk_load_id, run_at);
+
+    db::job::insert(&job, pool, tx).await
+}
+
+// One job per word, so every word is retried on its own
+pub async fn queue_load_definitions(
+    req: QueueLoadDefinitionsRequest,
+    sources: &DefinitionSources,
+    pool: &PgPool,
+) -> VortoResult<i32> {
+    sources.get(&req.source)?;
+    let words = word_service::filter_words(&req.filter, pool).await?;
+
+    let mut tx = pool.begin().await?;
+    for word in words.iter() {
+        let payload = LoadDefinitionsPayload {
+            word_id: word.id,
+            source: req.source,
+        };
+        enqueue(&JobKind::LoadDefinitions, &payload, pool, Some(&mut tx)).await?;
+    }
+    tx.commit().await?;
+
+    VortoResult::Ok(words.len() as i32)
+}
+
+// Transient source errors fail the job to be retried later,
+// the rest are saved with the word as a failed load
+async fn load_definitions(
+    job: &Job,
+    sources: &DefinitionSources,
+    client: &HttpClient,
+    pool: &PgPool,
+) -> VortoResult<()> {
+    let payload: LoadDefinitionsPayload = serde_json::from_str(&job.payload)?;
+    let source = sources.get(&payload.source)?;
+    let word = db::word::get_by_id(payload.word_id, pool).await?;
+
+    let page_result = raw_page_service::fetch_page(&word, source.as_ref(), client, pool).await?;
+    if let Err(parse_error) = &page_result {
+        if parse_error.is_transient() {
+            // The word gets the failure once the job gives up
+            if job.attempts >= job.max_attempts {
+                word_service::save_loaded_definitions(
+                    &word,
+                    word.timestamp,
+                    &page_result,
+                    &source.kind(),
+                    None,
+                    WordRevisionAction::Load,
+                    pool,
+                )
+                .await?;
+            }
+            return VortoResult::Err(VortoError::new(
+                VortoErrorCode::Parse,
+                parse_error.to_string(),
+            ));
+        }
+    }
+
+    word_service::save_loaded_definitions(
+        &word,
+        word.timestamp,
+        &page_result,
+        &source.kind(),
+        None,
+        WordRevisionAction::Load,
+        pool,
+    )
+    .await
+}
+
+async fn handle(
+    job: &Job,
+    sources: &DefinitionSources,
+    client: &HttpClient,
+    pool: &PgPool,
+) -> VortoResult<()> {
+    match JobKind::from_str(&job.kind) {
+        Ok(JobKind::LoadDefinitions) => load_definitions(job, sources, client, pool).await,
+        Ok(JobKind::SyncDefinitions) => {
+            sync_service::sync_definitions(job, sources, client, pool).await
+        }
+        Err(_) => VortoResult::Err(VortoError::new(
+            VortoErrorCode::Validation,
+            format!("Unknown job kind '{}'", job.kind),
+        )),
+    }
+}
+
+async fn heartbeat(id: i32, pool: PgPool) {
+    loop {
+        tokio::time::sleep(HEARTBEAT_INTERVAL).await;
+        if let VortoResult::Err(e) = db::job::heartbeat(id, Utc::now().naive_utc(), &pool).await {
+            error!("Heartbeat of job {} failed: {}", id, e.message);
+        }
+    }
+}
+
+// Returns false when there is nothing to do
+async fn run_next(
+    sources: &DefinitionSources,
+    client: &HttpClient,
+    pool: &PgPool,
+) -> VortoResult<bool> {
+    let now = Utc::now();
+    let stale_before = now - chrono::Duration::minutes(STALE_LOCK_MINUTES);
+
+    let mut tx = pool.begin().await?;
+    let job = match db::job::lock_next(now.naive_utc(), stale_before.naive_utc(), &mut tx).await? {
+        Some(job) => job,
+        None => return VortoResult::Ok(false),
+    };
+    // Failed or back to the queue, it is not run again right away
+    if job.status == JobStatus::Running.to_string() {
+        let (new_job, job_error) = domain::job::abandon(&job, &now);
+        db::job::update(&new_job, pool, Some(&mut tx)).await?;
+        db::job::insert_error(&job_error, pool, Some(&mut tx)).await?;
+        if domain::job::is_failed(&new_job) {
+            bulk_load_service::add_result(&new_job, Some(&job_error.error), pool, &mut tx).await?;
+        }
+        tx.commit().await?;
+
+        return VortoResult::Ok(true);
+    }
+    // Workers starting jobs of the same bulk load wait for each other on its row,
+    // so no more of them run than its concurrency allows
+    if let Some(bulk_load_id) = job.bulk_load_id {
+        if !bulk_load_service::has_free_slot(bulk_load_id, &mut tx).await? {
+            return VortoResult::Ok(false);
+        }
+    }
+    let job = domain::job::start(&job, &now);
+    db::job::update(&job, pool, Some(&mut tx)).await?;
+    tx.commit().await?;
+
+    let heartbeat = tokio::spawn(heartbeat(job.id, pool.clone()));
+    let result = handle(&job, sources, client, pool).await;
+    heartbeat.abort();
+
+    let now = Utc::now();
+    let mut tx = pool.begin().await?;
+    match result {
+        VortoResult::Ok(_) => {
+            let new_job = domain::job::complete(&job, &now);
+            if db::job::update_if_status(&new_job, &[JobStatus::Running], pool, Some(&mut tx))
+                .await?
+            {
+                bulk_load_service::add_result(&new_job, None, pool, &mut tx).await?;
+            }
+        }
+        VortoResult::Err(e) => {
+            let (new_job, job_error) = domain::job::fail(&job, &e.message, &now);
+            if db::job::update_if_status(&new_job, &[JobStatus::Running], pool, Some(&mut tx))
+                .await?
+            {
+                db::job::insert_error(&job_error, pool, Some(&mut tx)).await?;
+                if domain::job::is_failed(&new_job) {
+                    bulk_load_service::add_result(&new_job, Some(&job_error.error), pool, &mut tx)
+                        .await?;
+                }
+            }
+        }
+    }
+    tx.commit().await?;
+
+    VortoResult::Ok(true)
+}
+
+async fn work(worker: usize, sources: DefinitionSources, client: HttpClient, pool: PgPool) {
+    loop {
+        match run_next(&sources, &client, &pool).await {
+            VortoResult::Ok(true) => {}
+            VortoResult::Ok(false) => tokio::time::sleep(POLL_INTERVAL).await,
+            VortoResult::Err(e) => {
+                error!("Job worker {} failed: {}", worker, e.message);
+                tokio::time::sleep(POLL_INTERVAL).await;
+            }
+        }
+    }
+}
+
+pub fn start_workers(rocket: &rocket::Rocket<Orbit>) {
+    let pool = rocket.state::<PgPool>().expect("Pg poll not found");
+    let sources = rocket
+        .state::<DefinitionSources>()
+        .expect("Definition sources not found");
+    let client = rocket.state::<HttpClient>().expect("HTTP client not found");
+    let count = env_or(JOB_WORKERS, DEFAULT_JOB_WORKERS);
+
+    for worker in 0..count {
+        tokio::spawn(work(worker, sources.clone(), client.clone(), pool.clone()));
+    }
+    info!("Job workers started: {}", count);
+}
+
+fn job_view(job: &Job, errors: &Vec<JobError>) -> JobView {
+    JobView {
+        id: job.id,
+        kind: JobKind::from_str(&job.kind).unwrap(),
+        payload: job.payload.clone(),
+        status: JobStatus::from_str(&job.status).unwrap(),
+        attempts: job.attempts,
+        max_attempts: job.max_attempts,
+        run_at: job.run_at,
+        created_at: job.created_at,
+        finished_at: job.finished_at,
+        last_error: job.last_error.clone(),
+        errors: errors
+            .iter()
+            .map(|e| JobErrorView {
+                attempt: e.attempt,
+                error: e.error.clone(),
+                created_at: e.created_at,
+            })
+            .collect(),
+    }
+}
+
+async fn job_views(jobs: &Vec<Job>, pool: &PgPool) -> VortoResult<Vec<JobView>> {
+    let job_ids = jobs.iter().map(|j| j.id).collect();
+    let mut errors_by_job_id: HashMap<i32, Vec<JobError>> = HashMap::new();
+    for job_error in db::job::get_errors_by_job_ids(&job_ids, pool).await? {
+        errors_by_job_id
+            .entry(job_error.job_id)
+            .or_default()
+            .push(job_error);
+    }
+
+    VortoResult::Ok(
+        jobs.iter()
+            .map(|j| job_view(j, errors_by_job_id.get(&j.id).unwrap_or(&vec![])))
+            .collect(),
+    )
+}
+
+pub async fn search(req: &JobSearchRequest, pool: &PgPool) -> VortoResult<Vec<JobView>> {
+    let jobs = db::job::search(&req.statuses, &req.kinds, req.skip, req.take, pool).await?;
+
+    job_views(&jobs, pool).await
+}
+
+fn job_changed<T>() -> VortoResult<T> {
+    VortoResult::Err(VortoError::new(
+        VortoErrorCode::Validation,
+        "Job status has changed meanwhile, try again".to_owned(),
+    ))
+}
+
+pub async fn retry(id: i32, pool: &PgPool) -> VortoResult<JobView> {
+    let job = db::job::get_by_id(id, pool).await?;
+    let new_job = domain::job::retry(&job, &Utc::now())?;
+    if !db::job::update_if_status(&new_job, &domain::job::RETRYABLE_STATUSES, pool, None).await? {
+        return job_changed();
+    }
+
+    VortoResult::Ok(job_views(&vec![new_job], pool).await?.remove(0))
+}
+
+pub async fn cancel(id: i32, pool: &PgPool) -> VortoResult<JobView> {
+    let job = db::job::get_by_id(id, pool).await?;
+    let new_job = domain::job::cancel(&job, &Utc::now())?;
+    let mut tx = pool.begin().await?;
+    if !db::job::update_if_status(
+        &new_job,
+        &domain::job::CANCELLABLE_STATUSES,
+        pool,
+        Some(&mut tx),
+    )
+    .await?
+    {
+        return job_changed();
+    }
+    bulk_load_service::add_result(&new_job, Some("Job cancelled"), pool, &mut tx).await?;
+    tx.commit().await?;
+
+    VortoResult::Ok(job_views(&vec![new_job], pool).await?.remove(0))
+}
@@ -14,23 +14,31 @@ fn get_algorithm() -> VortoResult<Algorithm> {
     VortoResult::Ok(alg)
 }
 
-pub fn generate_token(now: DateTime<Utc>) -> VortoResult<String> {
+pub fn generate_token(user_id: i32, now: DateTime<Utc>) -> VortoResult<String> {
     let alg = get_algorithm()?;
     let header = json!({
         "alg": alg.name(),
         "iat": now.timestamp(),
         "exp": now + Duration::days(TOKEN_VALID_DAYS)
     });
-    let claims = json!({});
+    let claims = json!({ "sub": user_id.to_string() });
     let token = encode(&header, &claims, &alg)?;
 
     VortoResult::Ok(token)
 }
 
-pub fn verify(token: &str) -> VortoResult<()> {
+// Returns the id of the signed in user. Tokens issued before they carried the user have
+// no claims and no expiry that is checked, so they are not accepted even for a while:
+// admins signed in with them get 401 and have to sign in again
+pub fn verify(token: &str) -> VortoResult<i32> {
     let alg = get_algorithm()?;
     let verifier = Verifier::create().build()?;
 
-    let _ = verifier.verify(token, &alg)?;
-    VortoResult::Ok(())
+    let claims = verifier.verify(token, &alg)?;
+    if claims.get("sub").is_none() {
+        warn!("Token without a user is rejected, its admin has to sign in again");
+    }
+    let user_id = claims["sub"].as_str()?.parse::<i32>().ok()?;
+
+    VortoResult::Ok(user_id)
 }
@@ -6,3 +6,14 @@ pub mod voc_service;
 pub mod word_service;
 pub mod game_service;
 pub mod wiki_parser_service;
+pub mod definition_source;
+pub mod wikitext_parser_service;
+pub mod dump_import_service;
+pub mod bulk_load_service;
+pub mod http_client;
+pub mod job_service;
+pub mod raw_page_service;
+pub mod sync_service;
+pub mod word_import_service;
+pub mod word_export_service;
+pub mod saved_search_service;
@@ -0,0 +1,125 @@
+use std::{collections::HashMap, str::FromStr};
+
+use chrono::Utc;
+use sqlx::PgPool;
+
+use crate::{
+    db,
+    domain::{
+        self,
+        enums::{DefinitionSourceKind, WordRevisionAction},
+        raw_page::RawPage,
+        word::{ParsedPage, Word},
+    },
+    error::{ParseError, VortoErrorCode, VortoResult},
+    requests::ReparseRequest,
+    responses::ReparseView,
+    services::{
+        definition_source::{self, DefinitionSource},
+        http_client::HttpClient,
+        word_service,
+    },
+};
+
+const REPARSE_CHUNK_SIZE: usize = 500;
+
+pub async fn cache(
+    word_id: i32,
+    source: &DefinitionSourceKind,
+    content: &str,
+    pool: &PgPool,
+) -> VortoResult<()> {
+    let raw_page = domain::raw_page::new(word_id, source, content, &Utc::now());
+    db::raw_page::upsert(&raw_page, pool, None).await
+}
+
+// Page is cached before parsing, so a parser failure can be fixed and re-parsed later.
+// Failed fetch leaves the cached page as it was
+pub async fn fetch_page(
+    word: &Word,
+    source: &dyn DefinitionSource,
+    client: &HttpClient,
+    pool: &PgPool,
+) -> VortoResult<Result<ParsedPage, ParseError>> {
+    let content = match source.fetch(client, &word.body).await {
+        Ok(content) => content,
+        Err(parse_error) => return VortoResult::Ok(Err(parse_error)),
+    };
+    cache(word.id, &source.kind(), &content, pool).await?;
+
+    VortoResult::Ok(source.parse(&content))
+}
+
+// The requested source or the latest fetched page of a word
+fn pick_pages(
+    raw_pages: Vec<RawPage>,
+    source: &Option<DefinitionSourceKind>,
+) -> HashMap<i32, RawPage> {
+    let mut pages_by_word_id = HashMap::new();
+    for raw_page in raw_pages
+        .into_iter()
+        .filter(|p| source.map_or(true, |s| s.to_string() == p.source))
+    {
+        pages_by_word_id.entry(raw_page.word_id).or_insert(raw_page);
+    }
+    pages_by_word_id
+}
+
+// Runs the current parsers over cached pages, nothing is fetched.
+// A word that can't be saved is reported and the rest are still reparsed
+pub async fn reparse(req: &ReparseRequest, pool: &PgPool) -> VortoResult<ReparseView> {
+    let words = word_service::filter_words(&req.filter, pool).await?;
+    let mut view = ReparseView {
+        total: words.len() as i32,
+        reparsed: 0,
+        failed: 0,
+        not_cached: 0,
+        conflicts: vec![],
+        errors: vec![],
+    };
+
+    for chunk in words.chunks(REPARSE_CHUNK_SIZE) {
+        let word_ids = chunk.iter().map(|w| w.id).collect();
+        let pages_by_word_id = pick_pages(
+            db::raw_page::get_by_word_ids(&word_ids, pool).await?,
+            &req.source,
+        );
+
+        for word in chunk {
+            let raw_page = match pages_by_word_id.get(&word.id) {
+                Some(raw_page) => raw_page,
+                None => {
+                    view.not_cached += 1;
+                    continue;
+                }
+            };
+            let source = DefinitionSourceKind::from_str(&raw_page.source).unwrap();
+            let page_result = definition_source::parse_raw(&source, &raw_page.content);
+            let has_definitions = matches!(&page_result, Ok(p) if !p.definitions.is_empty());
+
+            let saved = word_service::save_loaded_definitions(
+                word,
+                word.timestamp,
+                &page_result,
+                &source,
+                None,
+                WordRevisionAction::Reparse,
+                pool,
+            )
+            .await;
+            match saved {
+                VortoResult::Ok(_) if has_definitions => view.reparsed += 1,
+                VortoResult::Ok(_) => view.failed += 1,
+                VortoResult::Err(e) if e.code == VortoErrorCode::Timestamp as i32 => {
+                    view.conflicts.push(word.body.clone())
+                }
+                VortoResult::Err(e) => {
+                    error!("Reparse of '{}' failed: {}", word.body, e.message);
+                    view.errors.push(format!("{}: {}", word.body, e.message));
+                }
+            }
+        }
+    }
+
+    VortoResult::Ok(view)
+}
@@ -0,0 +1,75 @@
+use chrono::Utc;
+use sqlx::PgPool;
+
+use crate::{
+    db,
+    domain::{self, saved_search::SavedSearch},
+    error::VortoResult,
+    requests::{RunSavedSearchRequest, SaveSearchRequest, SearchRequest},
+    responses::{SavedSearchView, WordSearchView},
+    services::word_service,
+};
+
+fn saved_search_view(saved_search: &SavedSearch) -> VortoResult<SavedSearchView> {
+    VortoResult::Ok(SavedSearchView {
+        id: saved_search.id,
+        name: saved_search.name.clone(),
+        filter: serde_json::from_str(&saved_search.filter)?,
+        field_orders: serde_json::from_str(&saved_search.field_orders)?,
+        created_at: saved_search.created_at,
+        updated_at: saved_search.updated_at,
+    })
+}
+
+async fn get_own(id: i32, user_id: i32, pool: &PgPool) -> VortoResult<SavedSearch> {
+    let saved_search = db::saved_search::get_by_id(id, pool).await?;
+    domain::saved_search::check_owner(&saved_search, user_id)?;
+
+    VortoResult::Ok(saved_search)
+}
+
+pub async fn save(req: &SaveSearchRequest, user_id: i32, pool: &PgPool) -> VortoResult<i32> {
+    let saved_search = domain::saved_search::new(
+        user_id,
+        &req.name,
+        &serde_json::to_string(&req.filter)?,
+        &serde_json::to_string(&req.field_orders)?,
+        &Utc::now(),
+    )?;
+
+    db::saved_search::upsert(&saved_search, pool, None).await
+}
+
+pub async fn get_all(user_id: i32, pool: &PgPool) -> VortoResult<Vec<SavedSearchView>> {
+    let mut views = vec![];
+    for saved_search in db::saved_search::get_by_user_id(user_id, pool).await? {
+        views.push(saved_search_view(&saved_search)?);
+    }
+
+    VortoResult::Ok(views)
+}
+
+pub async fn delete(id: i32, user_id: i32, pool: &PgPool) -> VortoResult<()> {
+    let saved_search = get_own(id, user_id, pool).await?;
+
+    db::saved_search::delete(saved_search.id, pool, None).await
+}
+
+pub async fn run(
+    id: i32,
+    req: &RunSavedSearchRequest,
+    user_id: i32,
+    pool: &PgPool,
+) -> VortoResult<WordSearchView> {
+    let saved_search = get_own(id, user_id, pool).await?;
+
+    let search_req = SearchRequest {
+        filter: serde_json::from_str(&saved_search.filter)?,
+        field_orders: serde_json::from_str(&saved_search.field_orders)?,
+        skip: req.skip,
+        take: req.take,
+        cursor: req.cursor.clone(),
+    };
+
+    word_service::search_page(&search_req, pool).await
+}
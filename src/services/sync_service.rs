@@ -0,0 +1,162 @@
+use std::{str::FromStr, time::Duration};
+
+use chrono::Utc;
+use rocket::Orbit;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use crate::{
+    common::env_or,
+    db,
+    domain::{
+        self,
+        enums::{DefinitionSourceKind, JobKind, WordLoadStatus, WordRevisionAction},
+        job::Job,
+    },
+    error::{VortoError, VortoErrorCode, VortoResult},
+    services::{
+        definition_source::DefinitionSources, http_client::HttpClient, job_service,
+        raw_page_service, word_service,
+    },
+};
+
+const RESYNC_AGE_DAYS: &str = "RESYNC_AGE_DAYS";
+const RESYNC_INTERVAL_MINUTES: &str = "RESYNC_INTERVAL_MINUTES";
+const RESYNC_BATCH_SIZE: &str = "RESYNC_BATCH_SIZE";
+const DEFAULT_RESYNC_AGE_DAYS: i64 = 30;
+const DEFAULT_RESYNC_INTERVAL_MINUTES: u64 = 60;
+const DEFAULT_RESYNC_BATCH_SIZE: i64 = 500;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SyncDefinitionsPayload {
+    pub word_id: i32,
+}
+
+// Words are marked as synced when queued, so the next pass doesn't queue them again
+pub async fn schedule(pool: &PgPool) -> VortoResult<i32> {
+    let now = Utc::now();
+    let synced_before =
+        now - chrono::Duration::days(env_or(RESYNC_AGE_DAYS, DEFAULT_RESYNC_AGE_DAYS));
+    let words = db::word::get_to_sync(
+        synced_before.naive_utc(),
+        env_or(RESYNC_BATCH_SIZE, DEFAULT_RESYNC_BATCH_SIZE),
+        pool,
+    )
+    .await?;
+
+    let mut tx = pool.begin().await?;
+    for word in words.iter() {
+        let payload = SyncDefinitionsPayload { word_id: word.id };
+        job_service::enqueue(&JobKind::SyncDefinitions, &payload, pool, Some(&mut tx)).await?;
+        db::word::set_synced_at(word.id, now.naive_utc(), pool, Some(&mut tx)).await?;
+    }
+    tx.commit().await?;
+
+    VortoResult::Ok(words.len() as i32)
+}
+
+// RESYNC_INTERVAL_MINUTES=0 turns the scheduler off
+pub fn start_scheduler(rocket: &rocket::Rocket<Orbit>) {
+    let pool = rocket.state::<PgPool>().expect("Pg poll not found").clone();
+    let interval = env_or(RESYNC_INTERVAL_MINUTES, DEFAULT_RESYNC_INTERVAL_MINUTES);
+    if interval == 0 {
+        return;
+    }
+
+    tokio::spawn(async move {
+        loop {
+            match schedule(&pool).await {
+                VortoResult::Ok(count) if count > 0 => info!("Re-sync queued: {}", count),
+                VortoResult::Ok(_) => {}
+                VortoResult::Err(e) => error!("Re-sync scheduling failed: {}", e.message),
+            }
+            tokio::time::sleep(Duration::from_secs(interval * 60)).await;
+        }
+    });
+}
+
+// Dump pages can't be fetched again, the API gives the same wikitext
+fn sync_source(source: DefinitionSourceKind) -> DefinitionSourceKind {
+    match source {
+        DefinitionSourceKind::WiktionaryDump => DefinitionSourceKind::WiktionaryApi,
+        source => source,
+    }
+}
+
+// Re-fetches from the source of the last cached page, an unchanged page needs no parsing
+pub async fn sync_definitions(
+    job: &Job,
+    sources: &DefinitionSources,
+    client: &HttpClient,
+    pool: &PgPool,
+) -> VortoResult<()> {
+    let payload: SyncDefinitionsPayload = serde_json::from_str(&job.payload)?;
+    let word = db::word::get_by_id(payload.word_id, pool).await?;
+    if word.load_status != WordLoadStatus::Loaded.to_string() {
+        return VortoResult::Ok(());
+    }
+
+    let previous_page = db::raw_page::get_by_word_ids(&vec![word.id], pool)
+        .await?
+        .into_iter()
+        .next();
+    let source_kind = previous_page
+        .as_ref()
+        .map(|p| sync_source(DefinitionSourceKind::from_str(&p.source).unwrap()))
+        .unwrap_or_default();
+    let source = sources.get(&source_kind)?;
+
+    let page_result = match source.fetch(client, &word.body).await {
+        Err(parse_error) if parse_error.is_transient() => {
+            return VortoResult::Err(VortoError::new(
+                VortoErrorCode::Parse,
+                parse_error.to_string(),
+            ));
+        }
+        Err(parse_error) => Err(parse_error),
+        Ok(content) => {
+            let is_same_page = previous_page.as_ref().map_or(false, |p| {
+                p.source == source_kind.to_string()
+                    && p.content_hash == domain::raw_page::content_hash(&content)
+            });
+            if is_same_page {
+                return db::word::set_synced_at(word.id, Utc::now().naive_utc(), pool, None).await;
+            }
+            raw_page_service::cache(word.id, &source_kind, &content, pool).await?;
+            source.parse(&content)
+        }
+    };
+
+    let vocs = word_service::load_vocs(&page_result, pool).await?;
+    let current_definitions = db::word_definition::get_by_word_id(word.id, pool).await?;
+
+    match domain::word::sync_definitions(
+        &word,
+        &page_result,
+        &current_definitions,
+        &vocs,
+        &source_kind,
+        &Utc::now(),
+    )? {
+        Some((new_word, new_word_definitions, new_word_relations)) => {
+            match word_service::save_definitions(
+                &new_word,
+                word.timestamp,
+                &new_word_definitions,
+                &new_word_relations,
+                None,
+                WordRevisionAction::Sync,
+                pool,
+            )
+            .await
+            {
+                // Edited during the fetch, the page is cached already so it is only flagged
+                VortoResult::Err(e) if e.code == VortoErrorCode::Timestamp as i32 => {
+                    db::word::set_has_upstream_changes(word.id, pool, None).await
+                }
+                result => result,
+            }
+        }
+        None => db::word::set_synced_at(word.id, Utc::now().naive_utc(), pool, None).await,
+    }
+}
@@ -53,7 +53,7 @@ pub async fn login(
 ) -> VortoResult<LoginResponse> {
     let user = get_by_email(&pool, email).await?;
     verify_password(pwd_hasher, password, &user.password_hash)?;
-    let token = generate_token(Utc::now())?;
+    let token = generate_token(user.id, Utc::now())?;
 
     VortoResult::Ok(LoginResponse {
         email: email.to_owned(),
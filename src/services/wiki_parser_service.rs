@@ -2,7 +2,14 @@ use std::collections::LinkedList;
 
 use std::{rc::Rc};
 
-use crate::error::{VortoError, VortoErrorCode, VortoResult};
+use crate::{
+    domain::{
+        enums::{Gender, PartOfSpeech, WordRelationKind},
+        word::{ParsedDefinition, ParsedPage},
+    },
+    error::ParseError,
+    services::http_client::HttpClient,
+};
 use html5ever::tendril::TendrilSink;
 use html5ever::{
     parse_document,
@@ -13,9 +20,73 @@ use markup5ever_rcdom::{Node, NodeData, RcDom};
 use regex::Regex;
 use urlencoding::encode;
 
-fn vocs_url(word: &str) -> String {
+const RU_SECTION: &str = "Русский";
+const RU_DEFINITIONS_SECTION: &str = "Значение";
+const EN_SECTION: &str = "English";
+const EN_PART_OF_SPEECH_SECTION: &str = "part of speech";
+const RU_MISSING_EXAMPLE: &str = "Отсутствует пример употребления";
+const RU_MORPHOLOGY_SECTION: &str = "Морфологические и синтаксические свойства";
+
+// First word of the morphology description, e.g. "Существительное, одушевлённое, мужской род"
+const RU_PARTS_OF_SPEECH: &[(&str, PartOfSpeech)] = &[
+    ("существительное", PartOfSpeech::Noun),
+    ("прилагательное", PartOfSpeech::Adjective),
+    ("глагол", PartOfSpeech::Verb),
+    ("причастие", PartOfSpeech::Participle),
+    ("наречие", PartOfSpeech::Adverb),
+    ("местоимение", PartOfSpeech::Pronoun),
+    ("числительное", PartOfSpeech::Numeral),
+    ("предлог", PartOfSpeech::Preposition),
+    ("союз", PartOfSpeech::Conjunction),
+    ("частица", PartOfSpeech::Particle),
+    ("междометие", PartOfSpeech::Interjection),
+];
+
+const RU_GENDERS: &[(&str, Gender)] = &[
+    ("общий род", Gender::Common),
+    ("мужской род", Gender::Masculine),
+    ("женский род", Gender::Feminine),
+    ("средний род", Gender::Neuter),
+];
+
+const RU_RELATION_SECTIONS: &[(&str, WordRelationKind)] = &[
+    ("Синонимы", WordRelationKind::Synonym),
+    ("Антонимы", WordRelationKind::Antonym),
+    ("Родственные слова", WordRelationKind::Related),
+];
+
+const EN_RELATION_SECTIONS: &[(&str, WordRelationKind)] = &[
+    ("Synonyms", WordRelationKind::Synonym),
+    ("Antonyms", WordRelationKind::Antonym),
+    ("Related terms", WordRelationKind::Related),
+];
+
+// Sections of a word's definitions, some of them have no part of speech of ours
+const EN_PARTS_OF_SPEECH: &[(&str, Option<PartOfSpeech>)] = &[
+    ("Noun", Some(PartOfSpeech::Noun)),
+    ("Proper noun", Some(PartOfSpeech::Noun)),
+    ("Verb", Some(PartOfSpeech::Verb)),
+    ("Adjective", Some(PartOfSpeech::Adjective)),
+    ("Adverb", Some(PartOfSpeech::Adverb)),
+    ("Pronoun", Some(PartOfSpeech::Pronoun)),
+    ("Preposition", Some(PartOfSpeech::Preposition)),
+    ("Conjunction", Some(PartOfSpeech::Conjunction)),
+    ("Interjection", Some(PartOfSpeech::Interjection)),
+    ("Numeral", Some(PartOfSpeech::Numeral)),
+    ("Determiner", None),
+    ("Article", None),
+    ("Particle", Some(PartOfSpeech::Particle)),
+    ("Phrase", None),
+    ("Prepositional phrase", None),
+    ("Proverb", None),
+    ("Idiom", None),
+];
+
+// `base_url` is a wiki root like https://ru.wiktionary.org or a local stand-in server
+fn vocs_url(base_url: &str, word: &str) -> String {
     format!(
-        "https://ru.wiktionary.org/w/index.php?title={}&printable=yes",
+        "{}/w/index.php?title={}&printable=yes",
+        base_url.trim_end_matches('/'),
         encode(word)
     )
 }
@@ -63,75 +134,199 @@ fn find_nodes(node: &Rc<Node>, find_name: &str, attributes: &Vec<Attr>) -> Vec<R
     nodes
 }
 
-fn find_node(node: &Rc<Node>, find_name: &str, attributes: &Vec<Attr>) -> Rc<Node> {
-    let nodes = find_nodes(node, find_name, attributes);
-
-    if let Some(node) = nodes.first() {
-        node.clone()
+fn has_class(node: &Rc<Node>, class: &str) -> bool {
+    if let NodeData::Element { attrs, .. } = &node.data {
+        attrs.borrow().iter().any(|a| {
+            a.name.local.to_string() == "class"
+                && a.value.split_whitespace().any(|c| c == class)
+        })
     } else {
+        false
+    }
+}
+
+// All descendants in document order, subtrees matched by `skip` are left out
+fn descendants(node: &Rc<Node>, skip: &impl Fn(&Rc<Node>) -> bool) -> Vec<Rc<Node>> {
+    let mut nodes = vec![];
+    let mut stack = LinkedList::new();
+    for c in node.children.borrow().iter().rev() {
+        stack.push_front(c.clone());
+    }
+
+    while let Some(n) = stack.pop_front() {
+        if skip(&n) {
+            continue;
+        }
+        for c in n.children.borrow().iter().rev() {
+            stack.push_front(c.clone());
+        }
+        nodes.push(n);
+    }
+    nodes
+}
+
+fn find_first(node: &Rc<Node>, find_name: &str, attributes: &Vec<Attr>) -> Option<Rc<Node>> {
+    find_nodes(node, find_name, attributes).first().cloned()
+}
+
+fn find_node(
+    node: &Rc<Node>,
+    find_name: &str,
+    attributes: &Vec<Attr>,
+    stage: &str,
+) -> Result<Rc<Node>, ParseError> {
+    find_first(node, find_name, attributes).ok_or_else(|| {
         let attrs_str = attributes
             .iter()
-            .map(|a| format!("(name: {}, value: {})", a.name, a.value))
+            .map(|a| format!("{}=\"{}\"", a.name, a.value))
             .collect::<Vec<_>>()
-            .join(", ");
+            .join(" ");
 
-        error!("Node with name '{}' and attrs: '{}' not found", find_name, attrs_str);
-        panic!("Node with name '{}' and attrs: '{}' not found", find_name, attrs_str);
-    }
+        ParseError::MissingNode {
+            node: format!("<{} {}>", find_name, attrs_str).replace(" >", ">"),
+            stage: stage.to_owned(),
+        }
+    })
+}
+
+fn find_node_with_class(
+    node: &Rc<Node>,
+    find_name: &str,
+    class: &str,
+    stage: &str,
+) -> Result<Rc<Node>, ParseError> {
+    find_nodes(node, find_name, &vec![])
+        .into_iter()
+        .find(|n| has_class(n, class))
+        .ok_or_else(|| ParseError::MissingNode {
+            node: format!("<{} class=\"{}\">", find_name, class),
+            stage: stage.to_owned(),
+        })
 }
 
 enum State {
     LangBlock,
+    Sections,
+    Morphology,
     Definitions,
-    Ol,
+    Relations(WordRelationKind),
 }
 
-fn get_text(node_data: &NodeData) -> String {
+fn get_text(node_data: &NodeData) -> Option<String> {
     if let NodeData::Text { contents } = node_data {
-        contents.borrow().to_string()
+        Some(contents.borrow().to_string())
     } else {
-        error!("Node is not a Text");
-        panic!("Node is not a Text");
+        None
     }
 }
 
 fn get_all_text(node: &Rc<Node>) -> String {
-    let mut stack = LinkedList::new();
-    let mut text = String::new();
-    stack.push_back(node.clone());
-
-    while !stack.is_empty() {
-        let n = stack.pop_front().unwrap();
-
-        text.push_str(&get_text(&n.data));
+    get_all_text_except(node, &|_| false)
+}
 
-        for c in n.children.borrow().iter().rev() {
-            stack.push_front(c.clone());
+fn get_all_text_except(node: &Rc<Node>, skip: &impl Fn(&Rc<Node>) -> bool) -> String {
+    let mut text = String::new();
+    if let NodeData::Text { contents } = &node.data {
+        text.push_str(&contents.borrow());
+    }
+    for n in descendants(node, skip) {
+        if let NodeData::Text { contents } = &n.data {
+            text.push_str(&contents.borrow());
         }
     }
     text
 }
 
+fn get_attr(node: &Rc<Node>, attr_name: &str) -> Option<String> {
+    if let NodeData::Element { attrs, .. } = &node.data {
+        attrs
+            .borrow()
+            .iter()
+            .find(|a| a.name.local.to_string() == attr_name)
+            .map(|a| a.value.to_string())
+    } else {
+        None
+    }
+}
+
+// Author, source and footnote marks of an example
+fn is_example_details(node: &Rc<Node>) -> bool {
+    has_class(node, "example-details")
+        || has_class(node, "example-source")
+        || get_element_name(node).as_deref() == Some("sup")
+}
+
 fn get_definition_with_vocs(node: &Rc<Node>) -> (Vec<String>, String) {
     let mut vocs = vec![];    
     let a_s = find_nodes(node, "a", &vec![]);
 
     for a in a_s {
-        if let VortoResult::Ok(span) = get_node_single_text(&a, "span", &vec![]) {
+        if let Some(span) = get_node_single_text(&a, "span", &vec![]) {
             vocs.push(span);
         }
     }
-    (vocs, get_all_text(&node))
+    (vocs, get_all_text_except(node, &is_example_details))
+}
+
+// Words linked from a relations block, links to service pages and footnotes are left out
+fn get_linked_words(node: &Rc<Node>) -> Vec<String> {
+    find_nodes(node, "a", &vec![])
+        .iter()
+        .filter(|a| {
+            let is_article = get_attr(a, "title").map_or(false, |t| !t.contains(':'));
+            let is_anchor = get_attr(a, "href").map_or(false, |h| h.starts_with('#'));
+            is_article && !is_anchor
+        })
+        .map(|a| replace_u(&get_all_text(a)).trim().to_owned())
+        .filter(|w| !w.is_empty())
+        .collect()
+}
+
+// The first block of a page wins, homonyms that follow it are not looked at
+fn parse_morphology(node: &Rc<Node>, page: &mut ParsedPage) {
+    if get_element_name(node).as_deref() != Some("p") {
+        return;
+    }
+    if page.syllables.is_none() {
+        if let Some(b) = find_first(node, "b", &vec![]) {
+            let syllables = replace_u(&get_all_text(&b)).trim().to_owned();
+            if !syllables.is_empty() {
+                page.syllables = Some(syllables);
+            }
+            return;
+        }
+    }
+    if page.part_of_speech.is_none() {
+        let text = get_all_text(node).to_lowercase();
+        let first_word = text
+            .split(|c: char| c == ',' || c == '.' || c.is_whitespace())
+            .next()
+            .unwrap_or_default();
+        if let Some(part_of_speech) = lookup(RU_PARTS_OF_SPEECH, first_word) {
+            page.part_of_speech = Some(part_of_speech);
+            page.gender = RU_GENDERS
+                .iter()
+                .find(|(g, _)| text.contains(g))
+                .map(|(_, gender)| *gender);
+        }
+    }
+}
+
+fn lookup<T: Copy>(table: &[(&str, T)], key: &str) -> Option<T> {
+    table
+        .iter()
+        .find(|(k, _)| *k == key)
+        .map(|(_, value)| *value)
 }
 
 fn get_node_single_text(
     node: &Rc<Node>,
     find_name: &str,
     attributes: &Vec<Attr>,
-) -> VortoResult<String> {
-    let fnode = find_node(&node, find_name, attributes);
+) -> Option<String> {
+    let fnode = find_first(node, find_name, attributes)?;
     let children = fnode.children.borrow();
-    VortoResult::Ok(get_text(&children.first()?.data))
+    get_text(&children.first()?.data)
 }
 
 fn replace_u(def: &str) -> String {
@@ -146,12 +341,16 @@ fn remove_multiple_spaces(def: &str) -> String {
     RE.replace_all(def, " ").into_owned()
 }
 
-fn remove_samples(def: &str) -> String {
-    if let Some(position) = def.find('◆') {
-        def[..position].to_owned()
-    } else {
-        def.to_owned()
-    }
+// Definition text and the usage examples which follow it after '◆'
+fn split_examples(def: &str) -> (String, Vec<String>) {
+    let mut parts = def.split('◆');
+    let def = parts.next().unwrap_or_default().to_owned();
+    let examples = parts
+        .map(|e| remove_multiple_spaces(&remove_noise(&replace_u(e))))
+        .filter(|e| !e.is_empty() && !e.starts_with(RU_MISSING_EXAMPLE))
+        .collect();
+
+    (def, examples)
 }
 
 fn remove_noise(def: &str) -> String {
@@ -170,10 +369,7 @@ fn remove_vocs(vocs: &Vec<String>, def: &str) -> String {
 }
 
 fn pretty_definition(vocs: &Vec<String>, def: &str) -> String {
-    remove_multiple_spaces(&remove_noise(&remove_samples(&remove_vocs(
-        vocs,
-        &replace_u(def),
-    ))))
+    remove_multiple_spaces(&remove_noise(&remove_vocs(vocs, &replace_u(def))))
 }
 
 fn get_element_name(node: &Rc<Node>) -> Option<String> {
@@ -190,57 +386,278 @@ fn get_element_name(node: &Rc<Node>) -> Option<String> {
     }
 }
 
-fn get_definitions(root: &Rc<Node>) -> VortoResult<Vec<(Vec<String>, String)>> {
-    let mw = find_node(&root, "div", &vec![Attr::new("class", "mw-parser-output")]);
+// Page level problems that are rendered instead of an article
+fn check_page(root: &Rc<Node>) -> Result<(), ParseError> {
+    if find_node_with_class(root, "div", "noarticletext", "page").is_ok() {
+        return Err(ParseError::PageNotFound);
+    }
+    if let Ok(redirect) = find_node_with_class(root, "div", "redirectMsg", "page") {
+        let target = find_first(&redirect, "a", &vec![])
+            .map(|a| get_all_text(&a))
+            .unwrap_or_default();
+        return Err(ParseError::Redirect(target));
+    }
+    if find_node_with_class(root, "span", "mw-redirectedfrom", "page").is_ok() {
+        let target = find_first(root, "h1", &vec![Attr::new("id", "firstHeading")])
+            .map(|h| get_all_text(&h).trim().to_owned())
+            .unwrap_or_default();
+        return Err(ParseError::Redirect(target));
+    }
+    Ok(())
+}
+
+fn get_definitions(root: &Rc<Node>) -> Result<ParsedPage, ParseError> {
+    check_page(root)?;
+    let mw = find_node_with_class(root, "div", "mw-parser-output", "page")?;
     let mut state = State::LangBlock;
-    let mut definitions = vec![];
+    let mut has_definitions_section = false;
+    let mut page = ParsedPage::default();
 
     for node in mw.children.borrow().iter() {
+        // Whitespace between blocks
+        if get_element_name(node).is_none() {
+            continue;
+        }
+        let heading = get_heading(node);
         match state {
             State::LangBlock => {
-                if let VortoResult::Ok(text) =
-                    get_node_single_text(&node, "span", &vec![Attr::new("class", "mw-headline")])
-                {
-                    if text == "Русский" {
-                        state = State::Definitions;
+                if let Some((_, text)) = heading {
+                    if text == RU_SECTION {
+                        state = State::Sections;
                     }
                 }
             }
-            State::Definitions => {
-                if let Some(elem_name) = get_element_name(&node) {
-                    if elem_name == "h1" {
+            State::Sections | State::Morphology | State::Relations(_) => {
+                if let Some((level, text)) = heading {
+                    if level == 1 {
                         break;
                     }
-                }
-
-                if let VortoResult::Ok(text) =
-                    get_node_single_text(&node, "span", &vec![Attr::new("class", "mw-headline")])
-                {
-                    if text == "Значение" {
-                        state = State::Ol;
-                    }
+                    state = if text == RU_DEFINITIONS_SECTION {
+                        has_definitions_section = true;
+                        State::Definitions
+                    } else if text == RU_MORPHOLOGY_SECTION {
+                        State::Morphology
+                    } else if let Some(kind) = lookup(RU_RELATION_SECTIONS, &text) {
+                        State::Relations(kind)
+                    } else {
+                        State::Sections
+                    };
+                } else if let State::Morphology = state {
+                    parse_morphology(node, &mut page);
+                } else if let State::Relations(kind) = state {
+                    page.relations
+                        .extend(get_linked_words(node).into_iter().map(|w| (kind, w)));
                 }
             }
-            State::Ol => {
-                let ol_node = find_node(&node, "ol", &vec![]);
+            State::Definitions => {
+                if heading.is_some() {
+                    return Err(ParseError::MissingNode {
+                        node: "<ol>".to_owned(),
+                        stage: RU_DEFINITIONS_SECTION.to_owned(),
+                    });
+                }
+                let ol_node = find_node(node, "ol", &vec![], RU_DEFINITIONS_SECTION)?;
                 for li in find_nodes(&ol_node, "li", &vec![]) {
-                    let (vocs, def) = get_definition_with_vocs(&li);
+                    let (vocs, text) = get_definition_with_vocs(&li);
+                    let (def, examples) = split_examples(&text);
                     let pretty_def = pretty_definition(&vocs, &def);
                     if !String::is_empty(&pretty_def) {
-                        definitions.push((vocs, pretty_def));
+                        page.definitions.push(ParsedDefinition {
+                            vocs,
+                            definition: pretty_def,
+                            examples,
+                        });
                     }
                     
                 }
-                state = State::Definitions;
+                state = State::Sections;
             }
         }
     }
 
-    VortoResult::Ok(definitions)
+    if let State::LangBlock = state {
+        return Err(ParseError::MissingSection(RU_SECTION.to_owned()));
+    }
+    if !has_definitions_section {
+        return Err(ParseError::MissingSection(RU_DEFINITIONS_SECTION.to_owned()));
+    }
+
+    Ok(page)
+}
+
+// Heading level and title, both for the old `<h2><span class="mw-headline">` markup
+// and the newer `<div class="mw-heading"><h2>` one
+fn get_heading(node: &Rc<Node>) -> Option<(u8, String)> {
+    let elem_name = get_element_name(node)?;
+    if elem_name == "div" && has_class(node, "mw-heading") {
+        return node.children.borrow().iter().find_map(get_heading);
+    }
+
+    let level = match elem_name.as_str() {
+        "h1" => 1,
+        "h2" => 2,
+        "h3" => 3,
+        "h4" => 4,
+        "h5" => 5,
+        "h6" => 6,
+        _ => return None,
+    };
+    let headline = find_first(node, "span", &vec![Attr::new("class", "mw-headline")])
+        .unwrap_or_else(|| node.clone());
+
+    Some((level, get_all_text(&headline).trim().to_owned()))
+}
+
+fn is_nested_block(node: &Rc<Node>) -> bool {
+    matches!(
+        get_element_name(node).as_deref(),
+        Some("ol") | Some("ul") | Some("dl") | Some("style") | Some("sup")
+    )
+}
+
+fn is_en_label(node: &Rc<Node>) -> bool {
+    has_class(node, "usage-label-sense") || has_class(node, "ib-brac") || has_class(node, "ib-content")
+}
+
+fn get_en_definition_with_vocs(li: &Rc<Node>) -> (Vec<String>, String) {
+    let mut vocs = vec![];
+    let labels = descendants(li, &is_nested_block)
+        .into_iter()
+        .filter(|n| has_class(n, "ib-content"));
+
+    for label in labels {
+        let links = find_nodes(&label, "a", &vec![]);
+        if links.is_empty() {
+            vocs.extend(get_all_text(&label).split(',').map(|v| v.trim().to_owned()));
+        } else {
+            vocs.extend(links.iter().map(|a| get_all_text(a).trim().to_owned()));
+        }
+    }
+    vocs.retain(|v| !v.is_empty());
+
+    let def = get_all_text_except(li, &|n| is_nested_block(n) || is_en_label(n));
+    (vocs, def)
 }
 
-pub async fn parse(word: &str) -> VortoResult<Vec<(Vec<String>, String)>> {
-    let text = reqwest::get(vocs_url(word)).await?.text().await?;
+fn pretty_en_definition(def: &str) -> String {
+    remove_multiple_spaces(&replace_u(def)).trim().to_owned()
+}
+
+// Usage examples and inline synonyms/antonyms are put into `<dl>` right under a definition
+fn get_en_examples_and_nyms(li: &Rc<Node>) -> (Vec<String>, Vec<(WordRelationKind, String)>) {
+    let mut examples = vec![];
+    let mut relations = vec![];
+    let dls = li
+        .children
+        .borrow()
+        .iter()
+        .filter(|c| get_element_name(c).as_deref() == Some("dl"))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    for node in dls.iter().flat_map(|dl| descendants(dl, &|_| false)) {
+        if has_class(&node, "nyms") {
+            let kind = if has_class(&node, "antonym") {
+                WordRelationKind::Antonym
+            } else {
+                WordRelationKind::Synonym
+            };
+            relations.extend(get_linked_words(&node).into_iter().map(|w| (kind, w)));
+        } else if has_class(&node, "e-example")
+            || (get_element_name(&node).as_deref() == Some("i") && has_class(&node, "mentioned"))
+        {
+            let example = pretty_en_definition(&get_all_text(&node));
+            if !example.is_empty() {
+                examples.push(example);
+            }
+        }
+    }
+    (examples, relations)
+}
+
+enum EnState {
+    LangBlock,
+    Sections,
+    PartOfSpeech,
+    Relations(WordRelationKind),
+}
+
+fn get_en_definitions(root: &Rc<Node>) -> Result<ParsedPage, ParseError> {
+    check_page(root)?;
+    let mw = find_node_with_class(root, "div", "mw-parser-output", "page")?;
+    let mut state = EnState::LangBlock;
+    let mut has_part_of_speech = false;
+    let mut page = ParsedPage::default();
+
+    for node in mw.children.borrow().iter() {
+        let heading = get_heading(node);
+        match state {
+            EnState::LangBlock => {
+                if let Some((2, text)) = heading {
+                    if text == EN_SECTION {
+                        state = EnState::Sections;
+                    }
+                }
+            }
+            EnState::Sections | EnState::PartOfSpeech | EnState::Relations(_) => {
+                if let Some((level, text)) = heading {
+                    if level <= 2 {
+                        break;
+                    }
+                    state = if let Some(part_of_speech) = lookup(EN_PARTS_OF_SPEECH, &text) {
+                        has_part_of_speech = true;
+                        if page.part_of_speech.is_none() {
+                            page.part_of_speech = part_of_speech;
+                        }
+                        EnState::PartOfSpeech
+                    } else if let Some(kind) = lookup(EN_RELATION_SECTIONS, &text) {
+                        EnState::Relations(kind)
+                    } else {
+                        EnState::Sections
+                    };
+                } else if let EnState::Relations(kind) = state {
+                    page.relations
+                        .extend(get_linked_words(node).into_iter().map(|w| (kind, w)));
+                } else if let EnState::PartOfSpeech = state {
+                    if get_element_name(node).as_deref() == Some("ol") {
+                        let items = node
+                            .children
+                            .borrow()
+                            .iter()
+                            .filter(|c| get_element_name(c).as_deref() == Some("li"))
+                            .cloned()
+                            .collect::<Vec<_>>();
+                        for li in items {
+                            let (vocs, def) = get_en_definition_with_vocs(&li);
+                            let pretty_def = pretty_en_definition(&def);
+                            if !pretty_def.is_empty() {
+                                let (examples, relations) = get_en_examples_and_nyms(&li);
+                                page.relations.extend(relations);
+                                page.definitions.push(ParsedDefinition {
+                                    vocs,
+                                    definition: pretty_def,
+                                    examples,
+                                });
+                            }
+                        }
+                        state = EnState::Sections;
+                    }
+                }
+            }
+        }
+    }
+
+    if let EnState::LangBlock = state {
+        return Err(ParseError::MissingSection(EN_SECTION.to_owned()));
+    }
+    if !has_part_of_speech {
+        return Err(ParseError::MissingSection(EN_PART_OF_SPEECH_SECTION.to_owned()));
+    }
+
+    Ok(page)
+}
+
+fn parse_dom(text: &str) -> Result<RcDom, ParseError> {
     let opts = ParseOpts {
         tree_builder: TreeBuilderOpts {
             drop_doctype: true,
@@ -252,5 +669,176 @@ pub async fn parse(word: &str) -> VortoResult<Vec<(Vec<String>, String)>> {
         .from_utf8()
         .read_from(&mut text.as_bytes())?;
 
-    VortoResult::Ok(get_definitions(&dom.document)?)
-}
\ No newline at end of file
+    Ok(dom)
+}
+
+// Saved ru.wiktionary.org pages can be fed here directly, see fixtures/wiktionary/ru
+pub fn parse_html(text: &str) -> Result<ParsedPage, ParseError> {
+    let dom = parse_dom(text)?;
+    get_definitions(&dom.document)
+}
+
+// Saved en.wiktionary.org pages can be fed here directly, see fixtures/wiktionary/en
+pub fn parse_en_html(text: &str) -> Result<ParsedPage, ParseError> {
+    let dom = parse_dom(text)?;
+    get_en_definitions(&dom.document)
+}
+
+// Printable page of either ru or en Wiktionary, they share the URL layout
+pub async fn fetch_html(
+    client: &HttpClient,
+    base_url: &str,
+    word: &str,
+) -> Result<String, ParseError> {
+    client.get_text(&vocs_url(base_url, word)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> String {
+        std::fs::read_to_string(format!(
+            "{}/fixtures/wiktionary/{}.html",
+            env!("CARGO_MANIFEST_DIR"),
+            name
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn parses_ru_page() {
+        let page = parse_html(&fixture("ru/kot")).unwrap();
+
+        assert_eq!(
+            page.definitions,
+            vec![
+                ParsedDefinition {
+                    vocs: vec!["зоол.".to_owned()],
+                    definition: "самец кошки".to_owned(),
+                    examples: vec!["Кот сидел на крыше.".to_owned()],
+                },
+                ParsedDefinition {
+                    vocs: vec!["разг.".to_owned()],
+                    definition: "то же, что кошка; домашнее животное".to_owned(),
+                    examples: vec![],
+                },
+                ParsedDefinition {
+                    vocs: vec![],
+                    definition: "то же, что морской котик".to_owned(),
+                    examples: vec![],
+                },
+            ]
+        );
+        assert_eq!(page.part_of_speech, Some(PartOfSpeech::Noun));
+        assert_eq!(page.gender, Some(Gender::Masculine));
+        assert_eq!(page.syllables, Some("ко\u{301}т".to_owned()));
+        assert!(page
+            .relations
+            .contains(&(WordRelationKind::Synonym, "котяра".to_owned())));
+    }
+
+    #[test]
+    fn fails_ru_page_without_russian_section() {
+        assert_eq!(
+            parse_html(&fixture("ru/no_russian_section")),
+            Err(ParseError::MissingSection("Русский".to_owned()))
+        );
+    }
+
+    #[test]
+    fn fails_ru_page_without_definitions_section() {
+        assert_eq!(
+            parse_html(&fixture("ru/no_definitions_section")),
+            Err(ParseError::MissingSection("Значение".to_owned()))
+        );
+    }
+
+    #[test]
+    fn fails_missing_ru_page() {
+        assert_eq!(
+            parse_html(&fixture("ru/not_found")),
+            Err(ParseError::PageNotFound)
+        );
+    }
+
+    #[test]
+    fn fails_ru_redirect_page() {
+        assert_eq!(
+            parse_html(&fixture("ru/redirect")),
+            Err(ParseError::Redirect("кот".to_owned()))
+        );
+    }
+
+    #[test]
+    fn parses_en_page() {
+        let page = parse_en_html(&fixture("en/cat")).unwrap();
+
+        assert_eq!(page.definitions.len(), 6);
+        assert_eq!(
+            page.definitions[1],
+            ParsedDefinition {
+                vocs: vec!["offensive".to_owned(), "archaic".to_owned()],
+                definition: "A spiteful or angry woman.".to_owned(),
+                examples: vec![],
+            }
+        );
+        assert_eq!(
+            page.definitions[3],
+            ParsedDefinition {
+                vocs: vec!["slang".to_owned()],
+                definition: "A man, a guy.".to_owned(),
+                examples: vec!["He's a cool cat.".to_owned()],
+            }
+        );
+        assert_eq!(
+            page.definitions[5].vocs,
+            vec!["transitive".to_owned(), "computing".to_owned()]
+        );
+        assert_eq!(page.part_of_speech, Some(PartOfSpeech::Noun));
+        assert_eq!(
+            page.relations,
+            vec![
+                (WordRelationKind::Synonym, "felid".to_owned()),
+                (WordRelationKind::Synonym, "puss".to_owned()),
+                (WordRelationKind::Synonym, "kitty".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_en_page_examples() {
+        let page = parse_en_html(&fixture("en/gift")).unwrap();
+
+        assert_eq!(
+            page.definitions,
+            vec![
+                ParsedDefinition {
+                    vocs: vec![],
+                    definition: "Something given to another voluntarily, without charge."
+                        .to_owned(),
+                    examples: vec!["She received a nice gift for her birthday.".to_owned()],
+                },
+                ParsedDefinition {
+                    vocs: vec![],
+                    definition: "A talent or natural ability.".to_owned(),
+                    examples: vec!["He has a gift for playing music.".to_owned()],
+                },
+                ParsedDefinition {
+                    vocs: vec!["informal".to_owned()],
+                    definition: "Something very easily obtained or done.".to_owned(),
+                    examples: vec![],
+                },
+                ParsedDefinition {
+                    vocs: vec!["transitive".to_owned()],
+                    definition: "To give as a gift or donation.".to_owned(),
+                    examples: vec![],
+                },
+            ]
+        );
+        assert_eq!(
+            page.relations,
+            vec![(WordRelationKind::Synonym, "present".to_owned())]
+        );
+    }
+}
@@ -0,0 +1,680 @@
+use regex::Regex;
+use serde::Deserialize;
+use urlencoding::encode;
+
+use crate::{
+    domain::{
+        enums::{Gender, PartOfSpeech, WordRelationKind},
+        word::{ParsedDefinition, ParsedPage},
+    },
+    error::ParseError,
+    services::http_client::HttpClient,
+};
+
+const RU_LANG_TEMPLATE: &str = "{{-ru-}}";
+const RU_SECTION: &str = "Русский";
+const DEFINITIONS_HEADING: &str = "Значение";
+const MORPHOLOGY_HEADING: &str = "Морфологические и синтаксические свойства";
+const MISSING_TITLE: &str = "missingtitle";
+
+// Leading word of a morphology template name, e.g. `{{сущ ru m a 1a}}` or `{{прил ru 1a}}`
+const PART_OF_SPEECH_TEMPLATES: &[(&str, PartOfSpeech)] = &[
+    ("сущ", PartOfSpeech::Noun),
+    ("прил", PartOfSpeech::Adjective),
+    ("гл", PartOfSpeech::Verb),
+    ("прич", PartOfSpeech::Participle),
+    ("adv", PartOfSpeech::Adverb),
+    ("нареч", PartOfSpeech::Adverb),
+    ("мест", PartOfSpeech::Pronoun),
+    ("числ", PartOfSpeech::Numeral),
+    ("prep", PartOfSpeech::Preposition),
+    ("предл", PartOfSpeech::Preposition),
+    ("conj", PartOfSpeech::Conjunction),
+    ("союз", PartOfSpeech::Conjunction),
+    ("part", PartOfSpeech::Particle),
+    ("частица", PartOfSpeech::Particle),
+    ("interj", PartOfSpeech::Interjection),
+    ("межд", PartOfSpeech::Interjection),
+];
+
+const RELATION_HEADINGS: &[(&str, WordRelationKind)] = &[
+    ("Синонимы", WordRelationKind::Synonym),
+    ("Антонимы", WordRelationKind::Antonym),
+    ("Родственные слова", WordRelationKind::Related),
+];
+
+#[derive(Deserialize)]
+struct ParseResponse {
+    parse: Option<ParsedWikitext>,
+    error: Option<ApiError>,
+}
+
+#[derive(Deserialize)]
+struct ParsedWikitext {
+    wikitext: String,
+}
+
+#[derive(Deserialize)]
+struct ApiError {
+    code: String,
+    info: String,
+}
+
+fn wikitext_url(base_url: &str, word: &str) -> String {
+    format!(
+        "{}/w/api.php?action=parse&page={}&prop=wikitext&format=json&formatversion=2",
+        base_url.trim_end_matches('/'),
+        encode(word)
+    )
+}
+
+// Labels and usage examples met while rendering a definition
+#[derive(Default)]
+struct Collected {
+    vocs: Vec<String>,
+    examples: Vec<String>,
+}
+
+struct Template {
+    name: String,
+    params: Vec<(Option<String>, String)>,
+}
+
+impl Template {
+    fn positional(&self, index: usize) -> Option<&String> {
+        self.params
+            .iter()
+            .filter(|(key, _)| key.is_none())
+            .nth(index)
+            .map(|(_, value)| value)
+    }
+
+    fn named(&self, name: &str) -> Option<&String> {
+        self.params
+            .iter()
+            .find(|(key, _)| key.as_deref() == Some(name))
+            .map(|(_, value)| value)
+    }
+}
+
+// Position right after the closing `close` matching the `open` at the start of `text`
+fn find_closing(text: &str, open: &str, close: &str) -> Option<usize> {
+    let mut depth = 0;
+    let mut i = 0;
+    while i < text.len() {
+        let rest = &text[i..];
+        if rest.starts_with(open) {
+            depth += 1;
+            i += open.len();
+        } else if rest.starts_with(close) {
+            depth -= 1;
+            i += close.len();
+            if depth == 0 {
+                return Some(i);
+            }
+        } else {
+            i += rest.chars().next()?.len_utf8();
+        }
+    }
+    None
+}
+
+// Splits by `sep` ignoring separators inside of nested templates and links
+fn split_top_level(text: &str, sep: char) -> Vec<String> {
+    let mut parts = vec![];
+    let mut current = String::new();
+    let mut depth = 0;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' | '[' if chars.peek() == Some(&c) => {
+                depth += 1;
+                current.push(c);
+                current.push(chars.next().unwrap());
+            }
+            '}' | ']' if chars.peek() == Some(&c) && depth > 0 => {
+                depth -= 1;
+                current.push(c);
+                current.push(chars.next().unwrap());
+            }
+            _ if c == sep && depth == 0 => parts.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    parts.push(current);
+    parts
+}
+
+fn parse_template(inner: &str) -> Template {
+    let mut parts = split_top_level(inner, '|').into_iter();
+    let name = parts.next().unwrap_or_default().trim().to_owned();
+    let params = parts
+        .map(|part| match part.find('=') {
+            Some(position)
+                if !part[..position].contains("{{") && !part[..position].contains("[[") =>
+            {
+                (
+                    Some(part[..position].trim().to_owned()),
+                    part[position + 1..].to_owned(),
+                )
+            }
+            _ => (None, part),
+        })
+        .collect();
+
+    Template { name, params }
+}
+
+fn render_template(template: &Template, collected: &mut Collected) -> String {
+    match template.name.as_str() {
+        "значение" => {
+            if let Some(labels) = template.named("пометы") {
+                render(labels, collected);
+            }
+            if let Some(examples) = template.named("примеры") {
+                render(examples, collected);
+            }
+            template
+                .named("определение")
+                .map(|def| render(def, collected))
+                .unwrap_or_default()
+        }
+        "помета" | "помета." => {
+            if let Some(label) = template.positional(0) {
+                let label = render(label, &mut Collected::default()).trim().to_owned();
+                if !label.is_empty() {
+                    collected.vocs.push(label);
+                }
+            }
+            String::new()
+        }
+        "пример" => {
+            if let Some(text) = template.named("текст").or_else(|| template.positional(0)) {
+                let example = remove_markup(&render(text, &mut Collected::default()));
+                if !example.is_empty() {
+                    collected.examples.push(example);
+                }
+            }
+            String::new()
+        }
+        "=" => template
+            .positional(0)
+            .map(|word| format!("то же, что {}", render(word, collected)))
+            .unwrap_or_default(),
+        "итп" => "и т. п.".to_owned(),
+        "итд" => "и т. д.".to_owned(),
+        "-" | "--" => "—".to_owned(),
+        "выдел" | "w" | "l" => template
+            .positional(0)
+            .map(|text| render(text, collected))
+            .unwrap_or_default(),
+        name if name.ends_with('.') => {
+            collected.vocs.push(name.to_owned());
+            String::new()
+        }
+        // The rest of presentation templates are not a part of a definition
+        _ => String::new(),
+    }
+}
+
+fn render_link(inner: &str) -> String {
+    let parts = split_top_level(inner, '|');
+    let target = parts.first().map(|p| p.trim()).unwrap_or_default();
+    if target.contains(':') {
+        String::new()
+    } else {
+        parts.last().cloned().unwrap_or_default()
+    }
+}
+
+// Renders a piece of wikitext to a plain text collecting labels (vocs) and examples on the way
+fn render(text: &str, collected: &mut Collected) -> String {
+    let mut result = String::new();
+    let mut i = 0;
+
+    while i < text.len() {
+        let rest = &text[i..];
+        if rest.starts_with("{{") {
+            if let Some(end) = find_closing(rest, "{{", "}}") {
+                let template = parse_template(&rest[2..end - 2]);
+                result.push_str(&render_template(&template, collected));
+                i += end;
+                continue;
+            }
+        } else if rest.starts_with("[[") {
+            if let Some(end) = find_closing(rest, "[[", "]]") {
+                result.push_str(&render(&render_link(&rest[2..end - 2]), collected));
+                i += end;
+                continue;
+            }
+        }
+        let c = rest.chars().next().unwrap();
+        result.push(c);
+        i += c.len_utf8();
+    }
+    result
+}
+
+fn remove_comments_and_refs(text: &str) -> String {
+    lazy_static! {
+        static ref COMMENT_RE: Regex = Regex::new(r"(?s)<!--.*?-->").unwrap();
+        static ref REF_RE: Regex = Regex::new(r"(?s)<ref[^>]*/>|<ref[^>]*>.*?</ref>").unwrap();
+    }
+    let text = COMMENT_RE.replace_all(text, "");
+    REF_RE.replace_all(&text, "").into_owned()
+}
+
+fn remove_markup(def: &str) -> String {
+    lazy_static! {
+        static ref TAG_RE: Regex = Regex::new(r"</?[a-zA-Z][^>]*>").unwrap();
+        static ref SPACES_RE: Regex = Regex::new(r"\s+").unwrap();
+    }
+    let def = TAG_RE.replace_all(def, "");
+    let def = def
+        .replace("'''", "")
+        .replace("''", "")
+        .replace("&nbsp;", " ")
+        .replace("\u{a0}", " ");
+
+    SPACES_RE
+        .replace_all(&def, " ")
+        .trim()
+        .trim_start_matches(", ")
+        .trim()
+        .to_owned()
+}
+
+fn get_heading(line: &str) -> Option<&str> {
+    lazy_static! {
+        static ref HEADING_RE: Regex = Regex::new(r"^=+\s*(.*?)\s*=+\s*$").unwrap();
+    }
+    HEADING_RE
+        .captures(line)
+        .map(|c| c.get(1).unwrap().as_str())
+}
+
+fn is_lang_heading(heading: &str) -> bool {
+    lazy_static! {
+        static ref LANG_RE: Regex = Regex::new(r"\{\{-[a-z-]+-(\|[^}]*)?\}\}").unwrap();
+    }
+    LANG_RE.is_match(heading)
+}
+
+fn get_redirect(text: &str) -> Option<String> {
+    lazy_static! {
+        static ref REDIRECT_RE: Regex =
+            Regex::new(r"(?i)^\s*#(redirect|перенаправление)\s*\[\[([^\]|#]+)").unwrap();
+    }
+    REDIRECT_RE
+        .captures(text)
+        .map(|c| c.get(2).unwrap().as_str().trim().to_owned())
+}
+
+fn is_balanced(text: &str) -> bool {
+    text.matches("{{").count() <= text.matches("}}").count()
+}
+
+// Link targets of a relations section line, links to other namespaces are left out
+fn get_linked_words(line: &str) -> Vec<String> {
+    lazy_static! {
+        static ref LINK_RE: Regex = Regex::new(r"\[\[([^\]|#]+)[^\]]*\]\]").unwrap();
+    }
+    LINK_RE
+        .captures_iter(&remove_comments_and_refs(line))
+        .map(|c| c.get(1).unwrap().as_str().trim().to_owned())
+        .filter(|w| !w.is_empty() && !w.contains(':'))
+        .collect()
+}
+
+fn get_definition(line: &str) -> ParsedDefinition {
+    let mut collected = Collected::default();
+    let line = remove_comments_and_refs(line);
+    let def = render(line.trim_start_matches('#').trim(), &mut collected);
+
+    ParsedDefinition {
+        vocs: collected.vocs,
+        definition: remove_markup(&def),
+        examples: collected.examples,
+    }
+}
+
+// Old format keeps examples on their own `#:` lines right after a definition
+fn get_example(line: &str) -> String {
+    let line = remove_comments_and_refs(line);
+    let mut collected = Collected::default();
+    let text = render(
+        line.trim_start_matches(|c| c == '#' || c == ':' || c == '*'),
+        &mut collected,
+    );
+    collected
+        .examples
+        .pop()
+        .unwrap_or_else(|| remove_markup(&text))
+}
+
+fn push_definition(page: &mut ParsedPage, definition: ParsedDefinition) {
+    if !definition.definition.is_empty() {
+        page.definitions.push(definition);
+    }
+}
+
+fn noun_gender(template: &Template) -> Option<Gender> {
+    let tokens = template.name.split_whitespace().collect::<Vec<_>>();
+    let gender = if tokens.len() > 2 {
+        // {{сущ ru m a 1a}}
+        match tokens[2] {
+            "m" => Gender::Masculine,
+            "f" => Gender::Feminine,
+            "n" => Gender::Neuter,
+            "mf" | "fm" => Gender::Common,
+            _ => return None,
+        }
+    } else {
+        // {{сущ-ru|кот|мо 1a}}
+        let index = template.positional(1)?;
+        match index.trim() {
+            i if i.starts_with("мж") => Gender::Common,
+            i if i.starts_with('м') => Gender::Masculine,
+            i if i.starts_with('ж') => Gender::Feminine,
+            i if i.starts_with('с') => Gender::Neuter,
+            _ => return None,
+        }
+    };
+    Some(gender)
+}
+
+fn find_templates(text: &str) -> Vec<Template> {
+    let mut templates = vec![];
+    let mut i = 0;
+    while let Some(start) = text[i..].find("{{") {
+        let start = i + start;
+        match find_closing(&text[start..], "{{", "}}") {
+            Some(end) => {
+                templates.push(parse_template(&text[start + 2..start + end - 2]));
+                i = start + end;
+            }
+            None => break,
+        }
+    }
+    templates
+}
+
+// Part of speech and gender come from the inflection template, syllables from `{{по-слогам}}`
+fn parse_morphology(text: &str, page: &mut ParsedPage) {
+    let text = remove_comments_and_refs(text);
+    for template in find_templates(&text) {
+        let name = template.name.to_lowercase();
+        let leading = name
+            .split(|c| c == ' ' || c == '-')
+            .next()
+            .unwrap_or_default();
+        if page.part_of_speech.is_none() {
+            if let Some((_, part_of_speech)) =
+                PART_OF_SPEECH_TEMPLATES.iter().find(|(t, _)| *t == leading)
+            {
+                page.part_of_speech = Some(*part_of_speech);
+                if *part_of_speech == PartOfSpeech::Noun {
+                    page.gender = noun_gender(&template);
+                }
+            }
+        }
+        if page.syllables.is_none() {
+            page.syllables = get_syllables(&template);
+        }
+    }
+}
+
+// `{{по-слогам|ко|то|фе́й}}` itself or nested into a `слоги=` parameter
+fn get_syllables(template: &Template) -> Option<String> {
+    if template.name != "по-слогам" {
+        return template
+            .named("слоги")
+            .and_then(|s| find_templates(s).iter().find_map(get_syllables));
+    }
+    let syllables = template
+        .params
+        .iter()
+        .filter(|(key, value)| key.is_none() && !matches!(value.trim(), "" | "."))
+        .map(|(_, value)| value.trim().to_owned())
+        .collect::<Vec<_>>()
+        .join("·");
+
+    Some(syllables).filter(|s| !s.is_empty())
+}
+
+enum State {
+    LangBlock,
+    Sections,
+    Morphology,
+    Definitions,
+    Relations(WordRelationKind),
+}
+
+// Definitions of the "Русский" section of a ru.wiktionary.org page source
+pub fn parse_wikitext(text: &str) -> Result<ParsedPage, ParseError> {
+    if let Some(target) = get_redirect(text) {
+        return Err(ParseError::Redirect(target));
+    }
+
+    let mut state = State::LangBlock;
+    let mut has_definitions_section = false;
+    let mut page = ParsedPage::default();
+    let mut morphology = String::new();
+    // `{{значение}}` of the old format often spans several lines
+    let mut pending = String::new();
+
+    for line in text.lines() {
+        let heading = get_heading(line);
+        if !pending.is_empty() {
+            // A template left open never spans a heading, the broken definition is dropped
+            // and the heading is taken as usual
+            if heading.is_some() {
+                pending.clear();
+            } else {
+                pending.push('\n');
+                pending.push_str(line);
+                if is_balanced(&pending) {
+                    push_definition(&mut page, get_definition(&std::mem::take(&mut pending)));
+                }
+                continue;
+            }
+        }
+
+        match state {
+            State::LangBlock => {
+                if let Some(title) = heading {
+                    if title.starts_with(RU_LANG_TEMPLATE) {
+                        state = State::Sections;
+                    }
+                }
+            }
+            State::Sections | State::Morphology | State::Definitions | State::Relations(_) => {
+                if let Some(title) = heading {
+                    if is_lang_heading(title) && !title.starts_with(RU_LANG_TEMPLATE) {
+                        break;
+                    }
+                    state = if title == DEFINITIONS_HEADING {
+                        has_definitions_section = true;
+                        State::Definitions
+                    } else if title == MORPHOLOGY_HEADING {
+                        State::Morphology
+                    } else if let Some((_, kind)) =
+                        RELATION_HEADINGS.iter().find(|(h, _)| *h == title)
+                    {
+                        State::Relations(*kind)
+                    } else {
+                        State::Sections
+                    };
+                } else if let State::Morphology = state {
+                    morphology.push_str(line);
+                    morphology.push('\n');
+                } else if let State::Relations(kind) = state {
+                    page.relations
+                        .extend(get_linked_words(line).into_iter().map(|w| (kind, w)));
+                } else if let State::Definitions = state {
+                    if line.starts_with("#:") || line.starts_with("#*") {
+                        let example = get_example(line);
+                        if let Some(last) = page.definitions.last_mut() {
+                            if !example.is_empty() {
+                                last.examples.push(example);
+                            }
+                        }
+                    } else if line.starts_with('#') {
+                        if !is_balanced(line) {
+                            pending.push_str(line);
+                            continue;
+                        }
+                        push_definition(&mut page, get_definition(line));
+                    }
+                }
+            }
+        }
+    }
+
+    if let State::LangBlock = state {
+        return Err(ParseError::MissingSection(RU_SECTION.to_owned()));
+    }
+    if !has_definitions_section {
+        return Err(ParseError::MissingSection(DEFINITIONS_HEADING.to_owned()));
+    }
+    parse_morphology(&morphology, &mut page);
+
+    Ok(page)
+}
+
+// Fetches the page source through the MediaWiki API, `base_url` is a wiki root
+// like https://ru.wiktionary.org or a local stand-in server
+pub async fn fetch_wikitext(
+    client: &HttpClient,
+    base_url: &str,
+    word: &str,
+) -> Result<String, ParseError> {
+    let text = client.get_text(&wikitext_url(base_url, word)).await?;
+
+    read_response(&text)
+}
+
+fn read_response(text: &str) -> Result<String, ParseError> {
+    let response: ParseResponse = serde_json::from_str(text)?;
+
+    match (response.parse, response.error) {
+        (Some(parsed), _) => Ok(parsed.wikitext),
+        (None, Some(error)) if error.code == MISSING_TITLE => Err(ParseError::PageNotFound),
+        (None, Some(error)) => Err(ParseError::InvalidContent(format!(
+            "{}: {}",
+            error.code, error.info
+        ))),
+        (None, None) => Err(ParseError::InvalidContent(
+            "MediaWiki API returned neither page nor error".to_owned(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn definition(vocs: &[&str], definition: &str, examples: &[&str]) -> ParsedDefinition {
+        ParsedDefinition {
+            vocs: vocs.iter().map(|v| v.to_string()).collect(),
+            definition: definition.to_owned(),
+            examples: examples.iter().map(|e| e.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn parses_meaning_template() {
+        let page = parse_wikitext(
+            "= {{-ru-}} =\n\
+             ==== Значение ====\n\
+             # {{значение\n\
+             |определение = самец [[кошка|кошки]]\n\
+             |пометы = {{зоол.|ru}}\n\
+             |примеры = {{пример|Кот сидел на крыше.}}\n\
+             }}\n\
+             # {{значение|определение={{=|кошка}}|пометы={{помета|разг.}}}}\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            page.definitions,
+            vec![
+                definition(&["зоол."], "самец кошки", &["Кот сидел на крыше."]),
+                definition(&["разг."], "то же, что кошка", &[]),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_label_template_and_example_lines() {
+        let page = parse_wikitext(
+            "= {{-ru-}} =\n\
+             ==== Значение ====\n\
+             # {{помета|перен.}} {{помета.|разг.}} очень большой человек\n\
+             #: {{пример|Ну и слон!}}\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            page.definitions,
+            vec![definition(
+                &["перен.", "разг."],
+                "очень большой человек",
+                &["Ну и слон!"]
+            )]
+        );
+    }
+
+    #[test]
+    fn drops_unclosed_template_at_heading() {
+        let page = parse_wikitext(
+            "= {{-ru-}} =\n\
+             ==== Значение ====\n\
+             # {{значение|определение = самец кошки\n\
+             # крупное млекопитающее\n\
+             ==== Синонимы ====\n\
+             # [[котяра]]\n\
+             = {{-uk-}} =\n\
+             ==== Значение ====\n\
+             # кіт\n",
+        )
+        .unwrap();
+
+        assert!(page.definitions.is_empty());
+        assert_eq!(
+            page.relations,
+            vec![(WordRelationKind::Synonym, "котяра".to_owned())]
+        );
+    }
+
+    #[test]
+    fn fails_redirect() {
+        assert_eq!(
+            parse_wikitext("#REDIRECT [[кот]]"),
+            Err(ParseError::Redirect("кот".to_owned()))
+        );
+        assert_eq!(
+            parse_wikitext("#перенаправление [[кот#Русский]]"),
+            Err(ParseError::Redirect("кот".to_owned()))
+        );
+    }
+
+    #[test]
+    fn reads_missing_title_as_not_found() {
+        assert_eq!(
+            read_response(r#"{"error":{"code":"missingtitle","info":"The page doesn't exist."}}"#),
+            Err(ParseError::PageNotFound)
+        );
+        assert_eq!(
+            read_response(r#"{"error":{"code":"badtitle","info":"Bad title."}}"#),
+            Err(ParseError::InvalidContent(
+                "badtitle: Bad title.".to_owned()
+            ))
+        );
+        assert_eq!(
+            read_response(r#"{"parse":{"title":"кот","wikitext":"= {{-ru-}} ="}}"#),
+            Ok("= {{-ru-}} =".to_owned())
+        );
+    }
+}
@@ -0,0 +1,155 @@
+use std::error::Error;
+
+use rocket::futures::Stream;
+use rocket::response::stream::stream;
+use serde::Serialize;
+use serde_json::json;
+use sqlx::PgPool;
+
+use crate::{
+    domain::enums::{
+        ExportFormat, Gender, PartOfSpeech, WordDefinitionStatus, WordLoadStatus, WordStatus,
+    },
+    error::VortoResult,
+    requests::ExportWordsRequest,
+    responses::WordView,
+    services::word_service::{self, FieldMatch, FieldOrder},
+};
+
+const EXPORT_CHUNK_SIZE: i64 = 500;
+const CSV_HEADER: &str = "id,body,status,difficulty,load_status,part_of_speech,gender,tags,\
+definition_order,definition,definition_status,voc,examples\n";
+
+#[derive(Serialize)]
+struct CsvExportRow<'a> {
+    id: i32,
+    body: &'a str,
+    status: WordStatus,
+    difficulty: i32,
+    load_status: WordLoadStatus,
+    part_of_speech: Option<PartOfSpeech>,
+    gender: Option<Gender>,
+    tags: String,
+    definition_order: Option<i32>,
+    definition: Option<&'a str>,
+    definition_status: Option<WordDefinitionStatus>,
+    voc: Option<&'a str>,
+    examples: String,
+}
+
+fn csv_row(word: &WordView, definition_index: Option<usize>) -> CsvExportRow<'_> {
+    let definition = definition_index.map(|i| &word.definitions[i]);
+    CsvExportRow {
+        id: word.id,
+        body: &word.body,
+        status: word.status,
+        difficulty: word.difficulty,
+        load_status: word.load_status,
+        part_of_speech: word.part_of_speech,
+        gender: word.gender,
+        tags: word.tags.join(";"),
+        definition_order: definition.map(|d| d.order),
+        definition: definition.map(|d| d.definition.as_str()),
+        definition_status: definition.map(|d| d.status),
+        voc: definition.and_then(|d| d.voc.as_ref().map(|v| v.short.as_str())),
+        examples: definition
+            .map(|d| d.examples.join(" | "))
+            .unwrap_or_default(),
+    }
+}
+
+// Words without definitions still get a row
+fn csv_lines(word: &WordView) -> Result<String, Box<dyn Error>> {
+    let mut writer = csv::WriterBuilder::new()
+        .has_headers(false)
+        .from_writer(vec![]);
+    let rows = if word.definitions.is_empty() {
+        vec![csv_row(word, None)]
+    } else {
+        (0..word.definitions.len())
+            .map(|i| csv_row(word, Some(i)))
+            .collect()
+    };
+    for row in rows {
+        writer.serialize(row)?;
+    }
+
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
+
+fn export_lines(format: ExportFormat, word: &WordView) -> Result<String, Box<dyn Error>> {
+    match format {
+        ExportFormat::Csv => csv_lines(word),
+        ExportFormat::JsonLines => Ok(format!("{}\n", serde_json::to_string(word)?)),
+    }
+}
+
+// The headers are already sent when an export fails, so the file ends with this record instead.
+// A CSV one has a single column and can't be mistaken for a word row
+fn error_line(format: ExportFormat, message: &str) -> String {
+    error!("Word export failed: {}", message);
+    match format {
+        ExportFormat::Csv => format!("\"Export failed: {}\"\n", message.replace('"', "\"\"")),
+        ExportFormat::JsonLines => format!("{}\n", json!({ "error": message })),
+    }
+}
+
+// Words are read in chunks by an id cursor and sent as soon as a chunk is read, vanished definitions
+// are left out. A failed read ends the output with an error record
+pub fn export(req: ExportWordsRequest, pool: PgPool) -> impl Stream<Item = String> {
+    let field_orders = vec![FieldOrder {
+        field_match: FieldMatch::Id,
+        is_asc: true,
+    }];
+
+    stream! {
+        if req.format == ExportFormat::Csv {
+            yield CSV_HEADER.to_owned();
+        }
+
+        let mut cursor = None;
+        loop {
+            let (words, next_cursor) = match word_service::search(
+                &req.filter,
+                &field_orders,
+                0,
+                cursor.as_ref(),
+                EXPORT_CHUNK_SIZE,
+                &pool,
+            )
+            .await
+            {
+                VortoResult::Ok(page) => page,
+                VortoResult::Err(e) => {
+                    yield error_line(req.format, &e.message);
+                    break;
+                }
+            };
+
+            for word in words.iter() {
+                let word = WordView {
+                    definitions: word
+                        .definitions
+                        .iter()
+                        .filter(|d| !d.is_vanished)
+                        .cloned()
+                        .collect(),
+                    ..word.clone()
+                };
+                let lines = export_lines(req.format, &word).map_err(|e| e.to_string());
+                match lines {
+                    Ok(lines) => yield lines,
+                    Err(e) => {
+                        yield error_line(req.format, &e);
+                        return;
+                    }
+                }
+            }
+
+            if next_cursor.is_none() {
+                break;
+            }
+            cursor = next_cursor;
+        }
+    }
+}
@@ -0,0 +1,182 @@
+use chrono::Utc;
+use serde::Deserialize;
+use sqlx::PgPool;
+
+use crate::{
+    common::reduce_results,
+    db,
+    domain::{
+        self,
+        enums::{ImportFormat, JobKind, WordRevisionAction, WordStatus},
+        word::ImportedWordDTO,
+    },
+    error::{VortoError, VortoErrorCode, VortoResult},
+    requests::ImportWordsRequest,
+    responses::WordImportView,
+    services::{
+        definition_source::DefinitionSources,
+        job_service::{self, LoadDefinitionsPayload},
+        word_service,
+    },
+};
+
+// Tags share a cell, e.g. "животные;дом"
+#[derive(Deserialize, Debug)]
+struct CsvWordRow {
+    body: String,
+    #[serde(default)]
+    difficulty: Option<i32>,
+    #[serde(default)]
+    status: Option<WordStatus>,
+    #[serde(default)]
+    tags: String,
+}
+
+fn row_error<T>(row: usize, message: String) -> VortoResult<T> {
+    VortoResult::Err(VortoError::new(
+        VortoErrorCode::Validation,
+        format!("Row {}: {}", row, message),
+    ))
+}
+
+// Spreadsheets saved in a russian locale separate cells with ';'
+fn csv_delimiter(content: &str) -> u8 {
+    let header = content.lines().next().unwrap_or_default();
+    if header.contains(';') && !header.contains(',') {
+        b';'
+    } else {
+        b','
+    }
+}
+
+fn parse_csv(content: &str) -> VortoResult<Vec<(usize, ImportedWordDTO)>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(csv_delimiter(content))
+        .trim(csv::Trim::All)
+        .flexible(true)
+        .from_reader(content.as_bytes());
+    let headers = match reader.headers() {
+        Ok(headers) => headers.clone(),
+        Err(e) => return row_error(1, e.to_string()),
+    };
+
+    reduce_results(
+        &reader
+            .records()
+            .enumerate()
+            .map(|(i, record)| {
+                // Header is the first line
+                let row = i + 2;
+                match record.and_then(|r| r.deserialize::<CsvWordRow>(Some(&headers))) {
+                    Ok(r) => VortoResult::Ok((
+                        row,
+                        ImportedWordDTO {
+                            body: r.body,
+                            difficulty: r.difficulty,
+                            status: r.status,
+                            tags: r
+                                .tags
+                                .split(|c| c == ';' || c == ',')
+                                .map(String::from)
+                                .collect(),
+                        },
+                    )),
+                    Err(e) => row_error(row, e.to_string()),
+                }
+            })
+            .collect(),
+    )
+}
+
+// Blank lines and lines starting with '#' are skipped
+fn parse_txt(content: &str) -> Vec<(usize, ImportedWordDTO)> {
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+        .map(|(i, line)| {
+            (
+                i + 1,
+                ImportedWordDTO {
+                    body: line.to_owned(),
+                    difficulty: None,
+                    status: None,
+                    tags: vec![],
+                },
+            )
+        })
+        .collect()
+}
+
+fn parse_json(content: &str) -> VortoResult<Vec<(usize, ImportedWordDTO)>> {
+    match serde_json::from_str::<Vec<ImportedWordDTO>>(content) {
+        Ok(rows) => VortoResult::Ok(
+            rows.into_iter()
+                .enumerate()
+                .map(|(i, r)| (i + 1, r))
+                .collect(),
+        ),
+        Err(e) => VortoResult::Err(VortoError::new(
+            VortoErrorCode::Validation,
+            format!("Invalid JSON: {}", e),
+        )),
+    }
+}
+
+// Nothing is imported when a row is invalid, all row errors are returned at once
+pub async fn import(
+    req: &ImportWordsRequest,
+    user_id: i32,
+    sources: &DefinitionSources,
+    pool: &PgPool,
+) -> VortoResult<WordImportView> {
+    if let Some(source) = &req.source {
+        sources.get(source)?;
+    }
+
+    let rows = match req.format {
+        ImportFormat::Csv => parse_csv(&req.content)?,
+        ImportFormat::Txt => parse_txt(&req.content),
+        ImportFormat::Json => parse_json(&req.content)?,
+    };
+    let existing_bodies = db::word::get_bodies(pool).await?;
+    let (words, duplicates) = domain::word::import(
+        &rows,
+        req.status,
+        req.difficulty,
+        &req.tags,
+        &existing_bodies,
+        &Utc::now(),
+    )?;
+
+    let mut tx = pool.begin().await?;
+    for word in words.iter() {
+        let word_id = db::word::insert(word, pool, Some(&mut tx)).await?;
+        let after = domain::word_revision::snapshot(word, &vec![]);
+        word_service::record_revision(
+            word_id,
+            Some(user_id),
+            WordRevisionAction::Create,
+            None,
+            &after,
+            pool,
+            &mut tx,
+        )
+        .await?;
+        if let Some(source) = req.source {
+            let payload = LoadDefinitionsPayload { word_id, source };
+            job_service::enqueue(&JobKind::LoadDefinitions, &payload, pool, Some(&mut tx)).await?;
+        }
+    }
+    tx.commit().await?;
+
+    VortoResult::Ok(WordImportView {
+        imported: words.len() as i32,
+        queued: if req.source.is_some() {
+            words.len() as i32
+        } else {
+            0
+        },
+        duplicates,
+    })
+}
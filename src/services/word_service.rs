@@ -1,33 +1,75 @@
-use std::{fmt::Display, str::FromStr};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    str::FromStr,
+};
 
-use crate::domain::enums::{WordDefinitionStatus, WordLoadStatus, WordStatus};
+use crate::domain::enums::{
+    DefinitionSourceKind, Gender, LoadFailKind, PartOfSpeech, TextSearchMode, WordDefinitionStatus,
+    WordLoadStatus, WordRelationKind, WordRevisionAction, WordStatus,
+};
 use crate::domain::voc::Voc;
-use crate::requests::UpdateWordRequest;
+use crate::domain::word::{ParsedDefinition, ParsedPage, Word, WordUpdate};
+use crate::domain::word_definition::WordDefinition;
+use crate::domain::word_relation::WordRelation;
+use crate::domain::word_revision::{WordRevision, WordSnapshot};
+use crate::requests::{
+    ArchiveWordRequest, BulkEditWordsRequest, CreateWordRequest, ResolveUpstreamChangesRequest,
+    RevertWordRequest, SearchRequest, UpdateWordRequest, WordFilter,
+};
 use crate::{
     common::{group, vec_to_map},
-    db,
-    error::VortoResult,
+    db::{self, common::QryBuilder},
+    error::{ParseError, VortoError, VortoErrorCode, VortoResult},
     responses::*,
 };
-use crate::{domain, wiki_parser_service};
-use chrono::Utc;
+use crate::domain;
+use crate::services::definition_source::DefinitionSource;
+use crate::services::http_client::HttpClient;
+use crate::services::raw_page_service;
+use chrono::{NaiveDateTime, Utc};
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
-use sqlx::{query_as, FromRow, PgPool};
+use sqlx::{Encode, FromRow, PgPool, Postgres, Transaction, Type};
 
-#[derive(Deserialize, Debug)]
+const ACTIVE_DEFINITIONS_COUNT_QRY: &str = "(SELECT count(*) FROM word_definitions d WHERE d.word_id = words.id AND d.status = 'active' AND NOT d.is_vanished)";
+const PLAYS_COUNT_QRY: &str = "(SELECT count(*) FROM word_results r WHERE r.word_id = words.id)";
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
 #[serde(rename_all = "snake_case")]
 pub enum FieldMatch {
+    Id,
     Body,
     Status,
     LoadStatus,
     Difficulty,
+    // Score of a text search
+    Relevance,
+    // Definitions that haven't vanished from the source
+    DefinitionCount,
+    EditedAt,
+    // Share of game rounds the word was guessed in
+    GuessRate,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
 pub struct FieldOrder {
-    field_match: FieldMatch,
-    is_asc: bool,
+    pub field_match: FieldMatch,
+    pub is_asc: bool,
+}
+
+// Sort values and id of the last word of a page, the next page starts after it
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct WordCursor {
+    pub field_orders: Vec<FieldOrder>,
+    pub values: Vec<String>,
+    pub id: i32,
+}
+
+#[derive(FromRow, Debug)]
+struct FacetQry {
+    value: String,
+    count: i64,
 }
 
 #[derive(Serialize, FromRow, Debug)]
@@ -37,6 +79,17 @@ pub struct WordQry {
     pub status: String,
     pub is_edited_after_load: bool,
     pub load_status: String,
+    pub load_fail_reason: Option<String>,
+    pub load_fail_kind: Option<String>,
+    pub load_fail_http_status: Option<i32>,
+    pub load_fail_stage: Option<String>,
+    pub load_fail_attempts: i32,
+    pub synced_at: Option<NaiveDateTime>,
+    pub has_upstream_changes: bool,
+    pub tags: Vec<String>,
+    pub part_of_speech: Option<String>,
+    pub gender: Option<String>,
+    pub syllables: Option<String>,
     pub timestamp: i64,
     pub difficulty: i32,
 
@@ -44,128 +97,577 @@ pub struct WordQry {
     pub definition: Option<String>,
     pub word_definition_status: Option<String>,
     pub order: Option<i32>,
+    pub word_definition_source: Option<String>,
+    pub is_vanished: Option<bool>,
 
     pub voc_id: Option<i32>,
     pub full: Option<String>,
     pub short: Option<String>,
+
+    pub score: Option<f32>,
+    pub highlight: Option<String>,
+    pub sort_values: Vec<String>,
 }
 
-const TRUE: &str = "true";
+// `_` and `%` in a prefix are matched as they are
+fn like_prefix(text: &str) -> String {
+    format!(
+        "{}%",
+        text.replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_")
+    )
+}
 
-fn in_qry(field: &str, values: &Vec<String>) -> String {
-    format!("{} IN ({})", field, values.join(","))
+// Case and ё are folded in SQL and in the searched text the same way,
+// each of the forms has a trigram index
+fn body_expr(filter: &WordFilter) -> String {
+    let mut expr = "body".to_owned();
+    if filter.ignore_case {
+        expr = format!("lower({})", expr);
+    }
+    if filter.ignore_yo {
+        expr = format!("translate({}, 'Ёё', 'Ее')", expr);
+    }
+    expr
 }
 
-fn db_str<T: Display>(val: T) -> String {
-    format!("'{}'", val.to_string())
+fn body_text(filter: &WordFilter) -> String {
+    let mut text = filter.text.clone();
+    if filter.ignore_case {
+        text = text.to_lowercase();
+    }
+    if filter.ignore_yo {
+        text = text.replace('Ё', "Е").replace('ё', "е");
+    }
+    text
 }
 
-fn field_order_qry(field_order: &FieldOrder) -> String {
-    let field = match field_order.field_match {
-        FieldMatch::Body => "body",
-        FieldMatch::Status => "status",
-        FieldMatch::LoadStatus => "load_status",
-        FieldMatch::Difficulty => "difficulty",
-    };
-    let order = if field_order.is_asc { "ASC" } else { "DESC" };
-    format!("{} {}", field, order)
+// Stemming takes care of the case, ё is always folded to match the index
+fn definition_tsv(alias: &str) -> String {
+    format!(
+        "to_tsvector('russian', translate({}.definition, 'Ёё', 'Ее'))",
+        alias
+    )
 }
 
-pub async fn search(
-    text: &str,
-    statuses: &Vec<WordStatus>,
-    load_statuses: &Vec<WordLoadStatus>,
-    difficulties: &Vec<i32>,
-    field_order: &FieldOrder,
-    skip: i64,
-    take: i64,
-    pool: &PgPool,
-) -> VortoResult<Vec<WordView>> {
-    let text_q = if text.is_empty() {
-        TRUE.to_owned()
-    } else {
-        format!("body LIKE '{}%'", text)
-    };
+fn push_definition_tsquery(qb: &mut QryBuilder, filter: &WordFilter) {
+    qb.push("plainto_tsquery('russian', ")
+        .push_bind(filter.text.replace('Ё', "Е").replace('ё', "е"))
+        .push(")");
+}
 
-    let status_q = if statuses.is_empty() {
-        TRUE.to_owned()
-    } else {
-        in_qry("status", &statuses.iter().map(db_str).collect())
-    };
+fn push_text_filter(qb: &mut QryBuilder, filter: &WordFilter) {
+    match filter.text_mode {
+        TextSearchMode::Prefix => {
+            qb.push(&format!(" AND {} LIKE ", body_expr(filter)))
+                .push_bind(like_prefix(&body_text(filter)));
+        }
+        TextSearchMode::Fuzzy => {
+            qb.push(&format!(" AND {} % ", body_expr(filter)))
+                .push_bind(body_text(filter));
+        }
+        TextSearchMode::FullText => {
+            qb.push(&format!(
+                " AND EXISTS (SELECT 1 FROM word_definitions d WHERE d.word_id = words.id AND {} @@ ",
+                definition_tsv("d")
+            ));
+            push_definition_tsquery(qb, filter);
+            qb.push(")");
+        }
+    }
+}
+
+fn push_score(qb: &mut QryBuilder, filter: &WordFilter) {
+    if filter.text.is_empty() {
+        qb.push("NULL::REAL");
+        return;
+    }
+
+    match filter.text_mode {
+        TextSearchMode::Prefix | TextSearchMode::Fuzzy => {
+            qb.push(&format!("similarity({}, ", body_expr(filter)))
+                .push_bind(body_text(filter))
+                .push(")");
+        }
+        TextSearchMode::FullText => {
+            qb.push(&format!("(SELECT max(ts_rank({}, ", definition_tsv("d")));
+            push_definition_tsquery(qb, filter);
+            qb.push(")) FROM word_definitions d WHERE d.word_id = words.id)");
+        }
+    }
+}
+
+// Fragment of a matched definition, only a full-text search matches definitions
+fn push_definition_highlight(qb: &mut QryBuilder, filter: &WordFilter) {
+    if filter.text.is_empty() || filter.text_mode != TextSearchMode::FullText {
+        qb.push("NULL::TEXT");
+        return;
+    }
+
+    qb.push(&format!("CASE WHEN {} @@ ", definition_tsv("wd")));
+    push_definition_tsquery(qb, filter);
+    qb.push(" THEN ts_headline('russian', wd.definition, ");
+    push_definition_tsquery(qb, filter);
+    qb.push(") END");
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
 
-    let load_status_q = if load_statuses.is_empty() {
-        TRUE.to_owned()
+// pg_trgm lowercases the words itself, ё is folded only when asked as in body_expr
+fn fold_trgm_char(c: char, filter: &WordFilter) -> char {
+    let c = c.to_lowercase().next().unwrap_or(c);
+    if filter.ignore_yo && c == 'ё' {
+        'е'
     } else {
-        in_qry("load_status", &load_statuses.iter().map(db_str).collect())
+        c
+    }
+}
+
+// Words are the alphanumeric runs, with their char ranges
+fn trgm_words(chars: &[char]) -> Vec<(usize, usize)> {
+    let mut words = vec![];
+    let mut start = None;
+    for (i, c) in chars.iter().chain([' '].iter()).enumerate() {
+        match (start, c.is_alphanumeric()) {
+            (None, true) => start = Some(i),
+            (Some(s), false) => {
+                words.push((s, i));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    words
+}
+
+// A word is padded with two spaces in front and one after, as pg_trgm does
+fn padded_trgm_word(chars: &[char], filter: &WordFilter) -> Vec<char> {
+    [' ', ' ']
+        .iter()
+        .cloned()
+        .chain(chars.iter().map(|c| fold_trgm_char(*c, filter)))
+        .chain([' '].iter().cloned())
+        .collect()
+}
+
+// Chars of the body covered by a trigram it shares with the searched text
+fn fuzzy_marks(chars: &[char], filter: &WordFilter) -> Vec<bool> {
+    let text = filter.text.chars().collect::<Vec<_>>();
+    let text_trgms = trgm_words(&text)
+        .into_iter()
+        .flat_map(|(start, end)| {
+            padded_trgm_word(&text[start..end], filter)
+                .windows(3)
+                .map(|w| w.to_vec())
+                .collect::<Vec<_>>()
+        })
+        .collect::<HashSet<_>>();
+
+    let mut marks = vec![false; chars.len()];
+    for (start, end) in trgm_words(chars) {
+        let padded = padded_trgm_word(&chars[start..end], filter);
+        for (i, trgm) in padded.windows(3).enumerate() {
+            if text_trgms.contains(trgm) {
+                // Padding takes the first two places
+                for j in (i..i + 3).filter(|j| (2..end - start + 2).contains(j)) {
+                    marks[start + j - 2] = true;
+                }
+            }
+        }
+    }
+    marks
+}
+
+// Runs of marked chars go in <b></b>, the rest of the body is escaped as it is
+fn highlight(chars: &[char], marks: &[bool]) -> String {
+    let mut highlight = String::new();
+    for (is_marked, run) in &chars.iter().zip(marks.iter()).group_by(|(_, m)| **m) {
+        let text = escape_html(&run.map(|(c, _)| *c).collect::<String>());
+        if is_marked {
+            highlight.push_str(&format!("<b>{}</b>", text));
+        } else {
+            highlight.push_str(&text);
+        }
+    }
+    highlight
+}
+
+fn body_highlights(body: &str, filter: &WordFilter) -> Vec<String> {
+    if filter.text.is_empty() {
+        return vec![];
+    }
+
+    let chars = body.chars().collect::<Vec<_>>();
+    let marks = match filter.text_mode {
+        TextSearchMode::Prefix => {
+            let prefix_len = filter.text.chars().count();
+            (0..chars.len()).map(|i| i < prefix_len).collect::<Vec<_>>()
+        }
+        TextSearchMode::Fuzzy => fuzzy_marks(&chars, filter),
+        TextSearchMode::FullText => return vec![],
     };
 
-    let difficulty_q = if difficulties.is_empty() {
-        TRUE.to_owned()
+    if marks.contains(&true) {
+        vec![highlight(&chars, &marks)]
     } else {
-        in_qry(
-            "difficulty",
-            &difficulties.iter().map(|x| x.to_string()).collect(),
+        vec![]
+    }
+}
+
+fn to_strings<T: Display>(values: &Vec<T>) -> Vec<String> {
+    values.iter().map(|v| v.to_string()).collect()
+}
+
+// Words without a score go last in a relevance order, never played words go first
+// in a guess rate order
+fn sort_expr(field_match: FieldMatch, alias: &str) -> String {
+    match field_match {
+        FieldMatch::Id => format!("{}.id", alias),
+        FieldMatch::Body => format!("{}.body", alias),
+        FieldMatch::Status => format!("{}.status", alias),
+        FieldMatch::LoadStatus => format!("{}.load_status", alias),
+        FieldMatch::Difficulty => format!("{}.difficulty", alias),
+        FieldMatch::Relevance => format!("COALESCE({}.score, 0)", alias),
+        FieldMatch::DefinitionCount => format!(
+            "(SELECT count(*) FROM word_definitions d WHERE d.word_id = {}.id AND NOT d.is_vanished)",
+            alias
+        ),
+        FieldMatch::EditedAt => format!("{}.timestamp", alias),
+        FieldMatch::GuessRate => format!(
+            "COALESCE((SELECT avg(r.result::INT)::REAL FROM word_results r WHERE r.word_id = {}.id), -1)",
+            alias
+        ),
+    }
+}
+
+fn sort_type(field_match: FieldMatch) -> &'static str {
+    match field_match {
+        FieldMatch::Id | FieldMatch::Difficulty => "INT",
+        FieldMatch::Body | FieldMatch::Status | FieldMatch::LoadStatus => "TEXT",
+        FieldMatch::DefinitionCount | FieldMatch::EditedAt => "BIGINT",
+        FieldMatch::Relevance | FieldMatch::GuessRate => "REAL",
+    }
+}
+
+// Id breaks ties, so pages don't overlap
+fn field_orders_qry(field_orders: &Vec<FieldOrder>, alias: &str) -> String {
+    field_orders
+        .iter()
+        .map(|fo| {
+            format!(
+                "{} {}",
+                sort_expr(fo.field_match, alias),
+                if fo.is_asc { "ASC" } else { "DESC" }
+            )
+        })
+        .chain(std::iter::once(format!("{}.id ASC", alias)))
+        .join(", ")
+}
+
+// Sort values go to the next cursor as text and are cast back when it is used
+fn sort_values_qry(field_orders: &Vec<FieldOrder>, alias: &str) -> String {
+    format!(
+        "ARRAY[{}]::TEXT[]",
+        field_orders
+            .iter()
+            .map(|fo| format!("({})::TEXT", sort_expr(fo.field_match, alias)))
+            .join(", ")
+    )
+}
+
+// Words after the cursor: (a > x) OR (a = x AND b > y) OR ..., the id is the last key
+fn push_cursor(
+    qb: &mut QryBuilder,
+    field_orders: &Vec<FieldOrder>,
+    cursor: &WordCursor,
+    alias: &str,
+) -> VortoResult<()> {
+    if &cursor.field_orders != field_orders || cursor.values.len() != field_orders.len() {
+        return VortoResult::Err(VortoError::new(
+            VortoErrorCode::Validation,
+            "Cursor is of another order".to_owned(),
+        ));
+    }
+
+    let mut keys = field_orders
+        .iter()
+        .zip(cursor.values.iter())
+        .map(|(fo, value)| {
+            (
+                sort_expr(fo.field_match, alias),
+                sort_type(fo.field_match),
+                fo.is_asc,
+                value.clone(),
+            )
+        })
+        .collect::<Vec<_>>();
+    keys.push((format!("{}.id", alias), "INT", true, cursor.id.to_string()));
+
+    qb.push("(FALSE");
+    for i in 0..keys.len() {
+        qb.push(" OR (TRUE");
+        for (j, (expr, sql_type, is_asc, value)) in keys.iter().take(i + 1).enumerate() {
+            let op = if j < i {
+                "="
+            } else if *is_asc {
+                ">"
+            } else {
+                "<"
+            };
+            qb.push(&format!(" AND {} {} CAST(", expr, op))
+                .push_bind(value.clone())
+                .push(&format!(" AS {})", sql_type));
+        }
+        qb.push(")");
+    }
+    qb.push(")");
+
+    VortoResult::Ok(())
+}
+
+fn push_filter(qb: &mut QryBuilder, filter: &WordFilter) {
+    qb.push("TRUE");
+
+    if !filter.text.is_empty() {
+        push_text_filter(qb, filter);
+    }
+    if !filter.statuses.is_empty() {
+        qb.push(" AND ")
+            .push_in("status", to_strings(&filter.statuses));
+    }
+    if !filter.load_statuses.is_empty() {
+        qb.push(" AND ")
+            .push_in("load_status", to_strings(&filter.load_statuses));
+    }
+    if !filter.difficulties.is_empty() {
+        qb.push(" AND ")
+            .push_in("difficulty", filter.difficulties.clone());
+    }
+    if !filter.parts_of_speech.is_empty() {
+        qb.push(" AND ")
+            .push_in("part_of_speech", to_strings(&filter.parts_of_speech));
+    }
+    if !filter.genders.is_empty() {
+        qb.push(" AND ")
+            .push_in("gender", to_strings(&filter.genders));
+    }
+    if !filter.load_fail_kinds.is_empty() {
+        qb.push(" AND ")
+            .push_in("load_fail_kind", to_strings(&filter.load_fail_kinds));
+    }
+    if !filter.load_fail_stages.is_empty() {
+        qb.push(" AND ")
+            .push_in("load_fail_stage", filter.load_fail_stages.clone());
+    }
+    if let Some(has_upstream_changes) = filter.has_upstream_changes {
+        qb.push(" AND has_upstream_changes = ")
+            .push_bind(has_upstream_changes);
+    }
+    if let Some(is_edited_after_load) = filter.is_edited_after_load {
+        qb.push(" AND is_edited_after_load = ")
+            .push_bind(is_edited_after_load);
+    }
+    push_range(
+        qb,
+        ACTIVE_DEFINITIONS_COUNT_QRY,
+        filter.min_active_definitions,
+        filter.max_active_definitions,
+    );
+    if !filter.definition_vocs.is_empty() {
+        qb.push(
+            r#" AND EXISTS (SELECT 1 FROM word_definitions d
+                JOIN vocs v ON v.id = d.voc_id
+                WHERE d.word_id = words.id AND NOT d.is_vanished AND "#,
         )
-    };
-    let field_order_q = field_order_qry(&field_order);
-    let wq = query_as::<_, WordQry>(&format!(
+        .push_in("v.short", filter.definition_vocs.clone())
+        .push(")");
+    }
+    push_range(
+        qb,
+        "char_length(body)",
+        filter.min_body_length,
+        filter.max_body_length,
+    );
+    push_range(qb, PLAYS_COUNT_QRY, filter.min_plays, filter.max_plays);
+}
+
+fn push_range<'q, T: 'q + Encode<'q, Postgres> + Type<Postgres> + Send>(
+    qb: &mut QryBuilder,
+    expr: &str,
+    min: Option<T>,
+    max: Option<T>,
+) {
+    if let Some(min) = min {
+        qb.push(&format!(" AND {} >= ", expr)).push_bind(min);
+    }
+    if let Some(max) = max {
+        qb.push(&format!(" AND {} <= ", expr)).push_bind(max);
+    }
+}
+
+// All words matching the filter, ordered by id
+pub async fn filter_words(filter: &WordFilter, pool: &PgPool) -> VortoResult<Vec<Word>> {
+    let mut qb = QryBuilder::new("SELECT * FROM words WHERE ");
+    push_filter(&mut qb, filter);
+    let words = qb
+        .push(" ORDER BY id")
+        .build_as::<Word>()
+        .fetch_all(pool)
+        .await?;
+
+    VortoResult::Ok(words)
+}
+
+// A cursor takes the place of `skip`. A full page gives a cursor to the next one
+pub async fn search(
+    filter: &WordFilter,
+    field_orders: &Vec<FieldOrder>,
+    skip: i64,
+    cursor: Option<&WordCursor>,
+    take: i64,
+    pool: &PgPool,
+) -> VortoResult<(Vec<WordView>, Option<WordCursor>)> {
+    let mut qb = QryBuilder::new(
         r#" SELECT 
                 w.id,
                 w.body,
                 w.status,
                 w.is_edited_after_load, 
                 w.load_status,
+                w.load_fail_reason,
+                w.load_fail_kind,
+                w.load_fail_http_status,
+                w.load_fail_stage,
+                w.load_fail_attempts,
+                w.synced_at,
+                w.has_upstream_changes,
+                w.tags,
+                w.part_of_speech,
+                w.gender,
+                w.syllables,
                 w.timestamp,
                 w.difficulty,
                 wd.id AS word_definition_id,
                 wd.definition,                
                 wd.status AS word_definition_status,
                 wd.order,
+                wd.source AS word_definition_source,
+                wd.is_vanished,
                 v.id AS voc_id,
                 v.full,
-                v.short
-            FROM (SELECT * 
-                FROM words
-                WHERE {} AND {} AND {} AND {}
-                ORDER BY {}
-                OFFSET {}
-                LIMIT {}) w
+                v.short,
+                w.score,
+                "#,
+    );
+    qb.push(&format!("{} AS sort_values, ", sort_values_qry(field_orders, "w")));
+    push_definition_highlight(&mut qb, filter);
+    qb.push(
+        r#" AS highlight
+            FROM (SELECT *
+                FROM (SELECT *, "#,
+    );
+    push_score(&mut qb, filter);
+    qb.push(
+        r#" AS score
+                    FROM words
+                    WHERE "#,
+    );
+    push_filter(&mut qb, filter);
+    qb.push(") s WHERE ");
+    match cursor {
+        Some(cursor) => push_cursor(&mut qb, field_orders, cursor, "s")?,
+        None => {
+            qb.push("TRUE");
+        }
+    }
+    qb.push(&format!(" ORDER BY {}", field_orders_qry(field_orders, "s")));
+    if cursor.is_none() {
+        qb.push(" OFFSET ").push_bind(skip);
+    }
+    let rows = qb
+        .push(" LIMIT ")
+        .push_bind(take)
+        .push(&format!(
+            r#") w
             LEFT JOIN word_definitions wd ON wd.word_id = w.id
             LEFT JOIN vocs v ON wd.voc_id = v.id
-            ORDER BY w.{}, wd.order"#,
-        text_q, status_q, load_status_q, difficulty_q, field_order_q, skip, take, field_order_q
-    ))
-    .fetch_all(pool)
-    .await?
-    .iter()
-    .map(|r| {
-        let word = WordView {
+            ORDER BY {}, wd.order"#,
+            field_orders_qry(field_orders, "w")
+        ))
+        .build_as::<WordQry>()
+        .fetch_all(pool)
+        .await?;
+    let next_cursor = match rows.last() {
+        Some(r) if rows.iter().map(|r| r.id).unique().count() as i64 == take => Some(WordCursor {
+            field_orders: field_orders.clone(),
+            values: r.sort_values.clone(),
             id: r.id,
-            body: r.body.clone(),
-            status: WordStatus::from_str(&r.status).unwrap(),
-            is_edited_after_load: r.is_edited_after_load,
-            load_status: WordLoadStatus::from_str(&r.load_status).unwrap(),
-            definitions: vec![],
-            timestamp: r.timestamp,
-            difficulty: r.difficulty,
-        };
-        let definition = r.word_definition_id.map(|wd_id| WordDefinitionView {
-            id: wd_id,
-            definition: r.definition.as_ref().unwrap().clone(),
-            status: WordDefinitionStatus::from_str(&r.word_definition_status.as_ref().unwrap())
-                .unwrap(),
-            order: r.order.unwrap(),
-            voc: r.voc_id.map(|voc_id| VocView {
-                id: voc_id,
-                full: r.full.as_ref().unwrap().clone(),
-                short: r.short.as_ref().unwrap().clone(),
-            }),
-        });
-        (word, definition)
-    })
-    .collect();
+        }),
+        _ => None,
+    };
+
+    let wq = rows
+        .iter()
+        .map(|r| {
+            let word = WordView {
+                id: r.id,
+                body: r.body.clone(),
+                status: WordStatus::from_str(&r.status).unwrap(),
+                is_edited_after_load: r.is_edited_after_load,
+                load_status: WordLoadStatus::from_str(&r.load_status).unwrap(),
+                load_fail_reason: r.load_fail_reason.clone(),
+                load_fail_kind: r
+                    .load_fail_kind
+                    .as_ref()
+                    .map(|k| LoadFailKind::from_str(k).unwrap()),
+                load_fail_http_status: r.load_fail_http_status,
+                load_fail_stage: r.load_fail_stage.clone(),
+                load_fail_attempts: r.load_fail_attempts,
+                synced_at: r.synced_at,
+                has_upstream_changes: r.has_upstream_changes,
+                tags: r.tags.clone(),
+                part_of_speech: r
+                    .part_of_speech
+                    .as_ref()
+                    .map(|p| PartOfSpeech::from_str(p).unwrap()),
+                gender: r.gender.as_ref().map(|g| Gender::from_str(g).unwrap()),
+                syllables: r.syllables.clone(),
+                definitions: vec![],
+                relations: vec![],
+                timestamp: r.timestamp,
+                difficulty: r.difficulty,
+                score: r.score,
+                highlights: body_highlights(&r.body, filter),
+            };
+            let definition = r.word_definition_id.map(|wd_id| WordDefinitionView {
+                id: wd_id,
+                definition: r.definition.as_ref().unwrap().clone(),
+                status: WordDefinitionStatus::from_str(r.word_definition_status.as_ref().unwrap())
+                    .unwrap(),
+                order: r.order.unwrap(),
+                voc: r.voc_id.map(|voc_id| VocView {
+                    id: voc_id,
+                    full: r.full.as_ref().unwrap().clone(),
+                    short: r.short.as_ref().unwrap().clone(),
+                }),
+                source: r
+                    .word_definition_source
+                    .as_ref()
+                    .map(|s| DefinitionSourceKind::from_str(s).unwrap()),
+                examples: vec![],
+                is_vanished: r.is_vanished.unwrap(),
+            });
+            (word, definition.map(|d| (d, r.highlight.clone())))
+        })
+        .collect();
 
-    let word_views = group(
+    let mut word_views: Vec<WordView> = group(
         &wq,
         |(word, _)| &word.id,
         |(word, _)| word.clone(),
@@ -173,24 +675,160 @@ pub async fn search(
     )
     .iter_mut()
     .map(|(w, wdv)| {
-        w.definitions = wdv.to_vec();
+        w.definitions = wdv.iter().map(|(d, _)| d.clone()).collect();
+        w.highlights
+            .extend(wdv.iter().filter_map(|(_, highlight)| highlight.clone()));
         w.clone()
     })
     .collect();
 
-    VortoResult::Ok(word_views)
+    attach_examples_and_relations(&mut word_views, pool).await?;
+
+    VortoResult::Ok((word_views, next_cursor))
+}
+
+async fn count(filter: &WordFilter, pool: &PgPool) -> VortoResult<i64> {
+    let mut qb = QryBuilder::new("SELECT count(*) FROM words WHERE ");
+    push_filter(&mut qb, filter);
+    let (total,) = qb.build_as::<(i64,)>().fetch_one(pool).await?;
+
+    VortoResult::Ok(total)
+}
+
+async fn facet(column: &str, filter: &WordFilter, pool: &PgPool) -> VortoResult<Vec<FacetQry>> {
+    let mut qb = QryBuilder::new(&format!(
+        "SELECT {}::TEXT AS value, count(*) AS count FROM words WHERE ",
+        column
+    ));
+    push_filter(&mut qb, filter);
+    let facets = qb
+        .push(&format!(" GROUP BY {} ORDER BY {}", column, column))
+        .build_as::<FacetQry>()
+        .fetch_all(pool)
+        .await?;
+
+    VortoResult::Ok(facets)
+}
+
+fn facet_views<T>(facets: &Vec<FacetQry>, value_fn: impl Fn(&str) -> T) -> Vec<FacetView<T>> {
+    facets
+        .iter()
+        .map(|f| FacetView {
+            value: value_fn(&f.value),
+            count: f.count,
+        })
+        .collect()
+}
+
+// A facet counts words by every other condition of the filter, so it shows what else can be picked
+async fn facets(filter: &WordFilter, pool: &PgPool) -> VortoResult<WordFacetsView> {
+    let statuses = facet(
+        "status",
+        &WordFilter {
+            statuses: vec![],
+            ..filter.clone()
+        },
+        pool,
+    )
+    .await?;
+    let load_statuses = facet(
+        "load_status",
+        &WordFilter {
+            load_statuses: vec![],
+            ..filter.clone()
+        },
+        pool,
+    )
+    .await?;
+    let difficulties = facet(
+        "difficulty",
+        &WordFilter {
+            difficulties: vec![],
+            ..filter.clone()
+        },
+        pool,
+    )
+    .await?;
+
+    VortoResult::Ok(WordFacetsView {
+        statuses: facet_views(&statuses, |v| WordStatus::from_str(v).unwrap()),
+        load_statuses: facet_views(&load_statuses, |v| WordLoadStatus::from_str(v).unwrap()),
+        difficulties: facet_views(&difficulties, |v| v.parse::<i32>().unwrap()),
+    })
+}
+
+pub async fn search_page(req: &SearchRequest, pool: &PgPool) -> VortoResult<WordSearchView> {
+    let (words, next_cursor) = search(
+        &req.filter,
+        &req.field_orders,
+        req.skip,
+        req.cursor.as_ref(),
+        req.take,
+        pool,
+    )
+    .await?;
+
+    VortoResult::Ok(WordSearchView {
+        total: count(&req.filter, pool).await?,
+        facets: facets(&req.filter, pool).await?,
+        words,
+        next_cursor,
+    })
+}
+
+async fn attach_examples_and_relations(
+    word_views: &mut Vec<WordView>,
+    pool: &PgPool,
+) -> VortoResult<()> {
+    let word_ids = word_views.iter().map(|w| w.id).collect();
+    let definition_ids = word_views
+        .iter()
+        .flat_map(|w| w.definitions.iter().map(|d| d.id))
+        .collect();
+
+    let mut examples_by_definition: HashMap<i32, Vec<String>> = HashMap::new();
+    for (definition_id, example) in
+        db::word_definition_example::get_by_word_definition_ids(&definition_ids, pool, None).await?
+    {
+        examples_by_definition
+            .entry(definition_id)
+            .or_default()
+            .push(example);
+    }
+
+    let mut relations_by_word: HashMap<i32, Vec<WordRelationView>> = HashMap::new();
+    for relation in db::word_relation::get_by_word_ids(&word_ids, pool).await? {
+        relations_by_word
+            .entry(relation.word_id)
+            .or_default()
+            .push(WordRelationView {
+                kind: WordRelationKind::from_str(&relation.kind).unwrap(),
+                body: relation.body,
+            });
+    }
+
+    for word_view in word_views.iter_mut() {
+        word_view.relations = relations_by_word.remove(&word_view.id).unwrap_or_default();
+        for definition in word_view.definitions.iter_mut() {
+            definition.examples = examples_by_definition
+                .remove(&definition.id)
+                .unwrap_or_default();
+        }
+    }
+
+    VortoResult::Ok(())
 }
 
-fn get_all_vocs(def_with_vocs: &Vec<(Vec<String>, String)>) -> Vec<String> {
-    def_with_vocs
+fn get_all_vocs(definitions: &Vec<ParsedDefinition>) -> Vec<String> {
+    definitions
         .iter()
-        .flat_map(|x| x.0.clone())
+        .flat_map(|x| x.vocs.clone())
         .unique()
         .collect()
 }
 
 async fn _load_vocs_to_definitions_new(
-    definitions: &Vec<(Vec<String>, String)>,
+    definitions: &Vec<ParsedDefinition>,
     pool: &PgPool,
 ) -> VortoResult<Vec<(Vec<Voc>, String)>> {
     let shorts = get_all_vocs(definitions);
@@ -200,89 +838,722 @@ async fn _load_vocs_to_definitions_new(
     VortoResult::Ok(
         definitions
             .iter()
-            .map(|(vocs, def)| {
-                let db_vocs = vocs
+            .map(|parsed| {
+                let db_vocs = parsed
+                    .vocs
                     .iter()
                     .filter_map(|v| vocs_short_map.get(v))
                     .map(Clone::clone)
                     .collect::<Vec<_>>();
-                (db_vocs, def.clone())
+                (db_vocs, parsed.definition.clone())
             })
             .collect::<Vec<_>>(),
     )
 }
 
-async fn load_vocs_to_definitions(
-    definitions_result: &VortoResult<Vec<(Vec<String>, String)>>,
+pub async fn load_vocs(
+    page_result: &Result<ParsedPage, ParseError>,
     pool: &PgPool,
-) -> VortoResult<Vec<(Option<Voc>, String)>> {
-    match definitions_result {
-        VortoResult::Ok(definitions) => {
-            let shorts = get_all_vocs(definitions);
+) -> VortoResult<HashMap<String, Voc>> {
+    match page_result {
+        Ok(page) => {
+            let shorts = get_all_vocs(&page.definitions);
             let vocs = db::voc::get_by_shorts(&shorts, pool).await?;
-            let vocs_short_map = vec_to_map(&vocs, |v| v.short.clone(), |v| v.clone());
-            VortoResult::Ok(
-                definitions
-                    .iter()
-                    .map(|(vocs, def)| {
-                        let first_db_voc = vocs
-                            .iter()
-                            .next()
-                            .and_then(|voc| vocs_short_map.get(voc))
-                            .map(Clone::clone);
-                        (first_db_voc, def.clone())
-                    })
-                    .collect::<Vec<_>>(),
-            )
+            VortoResult::Ok(vec_to_map(&vocs, |v| v.short.clone(), |v| v.clone()))
         }
-        VortoResult::Err(_) => VortoResult::Ok(vec![]),
+        Err(_) => VortoResult::Ok(HashMap::new()),
     }
 }
 
-pub async fn load_definitions(id: i32, timestamp: i64, pool: &PgPool) -> VortoResult<()> {
+pub async fn load_definitions(
+    id: i32,
+    timestamp: i64,
+    source: &dyn DefinitionSource,
+    client: &HttpClient,
+    user_id: i32,
+    pool: &PgPool,
+) -> VortoResult<()> {
     let word = db::word::get_by_id(id, pool).await?;
-    let definitions_result = wiki_parser_service::parse(&word.body).await;
-    let db_voc_and_defs = load_vocs_to_definitions(&definitions_result, pool).await;
+    let page_result = raw_page_service::fetch_page(&word, source, client, pool).await?;
 
-    let (new_word, new_word_definitions) =
-        domain::word::load_definitions(&word, timestamp, &db_voc_and_defs, &Utc::now())?;
+    save_loaded_definitions(
+        &word,
+        timestamp,
+        &page_result,
+        &source.kind(),
+        Some(user_id),
+        WordRevisionAction::Load,
+        pool,
+    )
+    .await
+}
+
+fn word_definition_view(
+    word_definition: &WordDefinition,
+    vocs_by_id: &HashMap<i32, Voc>,
+) -> WordDefinitionView {
+    WordDefinitionView {
+        id: word_definition.id,
+        definition: word_definition.definition.clone(),
+        status: WordDefinitionStatus::from_str(&word_definition.status).unwrap(),
+        order: word_definition.order,
+        voc: word_definition
+            .voc_id
+            .and_then(|voc_id| vocs_by_id.get(&voc_id))
+            .map(|v| VocView {
+                id: v.id,
+                full: v.full.clone(),
+                short: v.short.clone(),
+            }),
+        source: word_definition
+            .source
+            .as_ref()
+            .map(|s| DefinitionSourceKind::from_str(s).unwrap()),
+        examples: word_definition.examples.clone(),
+        is_vanished: word_definition.is_vanished,
+    }
+}
+
+// Runs the source and the load rules without saving anything, the page isn't cached either
+pub async fn preview_definitions(
+    id: i32,
+    source: &dyn DefinitionSource,
+    client: &HttpClient,
+    pool: &PgPool,
+) -> VortoResult<DefinitionsPreviewView> {
+    let word = db::word::get_by_id(id, pool).await?;
+    let page_result = match source.fetch(client, &word.body).await {
+        Ok(content) => source.parse(&content),
+        Err(parse_error) => Err(parse_error),
+    };
+    let vocs = load_vocs(&page_result, pool).await?;
 
+    let mut current_definitions = db::word_definition::get_by_word_id(word.id, pool).await?;
+    let mut examples_by_definition: HashMap<i32, Vec<String>> = HashMap::new();
+    for (definition_id, example) in db::word_definition_example::get_by_word_definition_ids(
+        &current_definitions.iter().map(|wd| wd.id).collect(),
+        pool,
+        None,
+    )
+    .await?
+    {
+        examples_by_definition
+            .entry(definition_id)
+            .or_default()
+            .push(example);
+    }
+    for definition in current_definitions.iter_mut() {
+        definition.examples = examples_by_definition
+            .remove(&definition.id)
+            .unwrap_or_default();
+    }
+
+    let (new_word, new_word_definitions, diff) = domain::word::preview_definitions(
+        &word,
+        &page_result,
+        &current_definitions,
+        &vocs,
+        &source.kind(),
+        &Utc::now(),
+    )?;
+
+    let voc_ids = current_definitions
+        .iter()
+        .filter_map(|wd| wd.voc_id)
+        .unique()
+        .collect();
+    let mut vocs_by_id = vec_to_map(
+        &db::voc::get_by_ids(&voc_ids, pool).await?,
+        |v| v.id,
+        |v| v.clone(),
+    );
+    vocs_by_id.extend(vocs.values().map(|v| (v.id, v.clone())));
+    let views = |definitions: &Vec<WordDefinition>| {
+        definitions
+            .iter()
+            .map(|wd| word_definition_view(wd, &vocs_by_id))
+            .collect()
+    };
+
+    VortoResult::Ok(DefinitionsPreviewView {
+        load_status: WordLoadStatus::from_str(&new_word.load_status).unwrap(),
+        load_fail_reason: new_word.load_fail_reason,
+        current: views(&current_definitions),
+        proposed: views(&new_word_definitions),
+        added: views(&diff.added),
+        removed: views(&diff.removed),
+        changed: diff
+            .changed
+            .iter()
+            .map(|(current, merged)| DefinitionChangeView {
+                current: word_definition_view(current, &vocs_by_id),
+                proposed: word_definition_view(merged, &vocs_by_id),
+                is_text_changed: current.definition != merged.definition,
+                is_voc_changed: current.voc_id != merged.voc_id,
+                is_restored: current.is_vanished && !merged.is_vanished,
+            })
+            .collect(),
+    })
+}
+
+pub async fn save_loaded_definitions(
+    word: &Word,
+    timestamp: i64,
+    page_result: &Result<ParsedPage, ParseError>,
+    source: &DefinitionSourceKind,
+    user_id: Option<i32>,
+    action: WordRevisionAction,
+    pool: &PgPool,
+) -> VortoResult<()> {
+    let vocs = load_vocs(page_result, pool).await?;
+    let current_definitions = db::word_definition::get_by_word_id(word.id, pool).await?;
+
+    let (new_word, new_word_definitions, new_word_relations) = domain::word::load_definitions(
+        word,
+        timestamp,
+        page_result,
+        &current_definitions,
+        &vocs,
+        source,
+        &Utc::now(),
+    )?;
+
+    save_definitions(
+        &new_word,
+        word.timestamp,
+        &new_word_definitions,
+        &new_word_relations,
+        user_id,
+        action,
+        pool,
+    )
+    .await
+}
+
+// Saved only if nobody has changed the word since it was read with `timestamp`,
+// a fetch takes a while and an edit may come meanwhile. Relations are replaced only when given.
+// A revision is recorded when the word changes the way an editor sees it, a failed load doesn't
+pub async fn save_definitions(
+    word: &Word,
+    timestamp: i64,
+    word_definitions: &Vec<WordDefinition>,
+    word_relations: &Option<Vec<WordRelation>>,
+    user_id: Option<i32>,
+    action: WordRevisionAction,
+    pool: &PgPool,
+) -> VortoResult<()> {
     let mut tx = pool.begin().await?;
-    db::word::update(&new_word, pool, Some(&mut tx)).await?;
+    let locked_word = match db::word::lock_by_ids(&vec![word.id], &mut tx).await?.pop() {
+        Some(locked_word) if locked_word.timestamp == timestamp => locked_word,
+        _ => {
+            return VortoResult::Err(VortoError::new(
+                VortoErrorCode::Timestamp,
+                format!("Word '{}' has changed meanwhile", word.body),
+            ));
+        }
+    };
+    let before_definitions = definitions_with_examples_by_word(&vec![word.id], pool, Some(&mut tx))
+        .await?
+        .remove(&word.id)
+        .unwrap_or_default();
+    db::word::update(word, pool, Some(&mut tx)).await?;
+
+    merge_definitions(word_definitions, pool, &mut tx).await?;
 
-    db::word_definition::delete_by_word_id(word.id, pool, Some(&mut tx)).await?;
-    for wd in new_word_definitions {
-        db::word_definition::insert(&wd, pool, Some(&mut tx)).await?;
+    if let Some(word_relations) = word_relations {
+        db::word_relation::delete_by_word_id(word.id, pool, Some(&mut tx)).await?;
+        for wr in word_relations.iter() {
+            db::word_relation::insert(wr, pool, Some(&mut tx)).await?;
+        }
+    }
+
+    // Read back, kept definitions are saved without their examples
+    let after_definitions = definitions_with_examples_by_word(&vec![word.id], pool, Some(&mut tx))
+        .await?
+        .remove(&word.id)
+        .unwrap_or_default();
+    let before = domain::word_revision::snapshot(&locked_word, &before_definitions);
+    let after = domain::word_revision::snapshot(word, &after_definitions);
+    if before != after {
+        record_revision(
+            word.id,
+            user_id,
+            action,
+            Some(&before),
+            &after,
+            pool,
+            &mut tx,
+        )
+        .await?;
     }
     tx.commit().await?;
 
     VortoResult::Ok(())
 }
 
-pub async fn update(req: UpdateWordRequest, pool: &PgPool) -> VortoResult<()> {
-    let word = db::word::get_by_id(req.id, pool).await?;
+async fn insert_definition(
+    word_definition: &WordDefinition,
+    pool: &PgPool,
+    tx: &mut Transaction<'_, Postgres>,
+) -> VortoResult<()> {
+    let word_definition_id = db::word_definition::insert(word_definition, pool, Some(&mut *tx)).await?;
+    for (order, example) in word_definition.examples.iter().enumerate() {
+        db::word_definition_example::insert(
+            word_definition_id,
+            example,
+            order as i32,
+            pool,
+            Some(&mut *tx),
+        )
+        .await?;
+    }
 
-    let (new_word, new_word_definitions) = domain::word::update(
-        &word,
-        &req.status,
+    VortoResult::Ok(())
+}
+
+// Kept definitions are updated in place, so their examples stay untouched
+async fn merge_definitions(
+    word_definitions: &Vec<WordDefinition>,
+    pool: &PgPool,
+    tx: &mut Transaction<'_, Postgres>,
+) -> VortoResult<()> {
+    for wd in word_definitions {
+        if wd.id == -1 {
+            insert_definition(wd, pool, tx).await?;
+        } else {
+            db::word_definition::update(wd, pool, Some(&mut *tx)).await?;
+        }
+    }
+
+    VortoResult::Ok(())
+}
+
+async fn replace_definitions(
+    word_id: i32,
+    word_definitions: &Vec<WordDefinition>,
+    pool: &PgPool,
+    tx: &mut Transaction<'_, Postgres>,
+) -> VortoResult<()> {
+    db::word_definition_example::delete_by_word_id(word_id, pool, Some(&mut *tx)).await?;
+    db::word_definition::delete_by_word_id(word_id, pool, Some(&mut *tx)).await?;
+    for wd in word_definitions {
+        insert_definition(wd, pool, tx).await?;
+    }
+
+    VortoResult::Ok(())
+}
+
+// Definitions of a word with their examples, as an editor sees them
+async fn definitions_with_examples(
+    word_id: i32,
+    pool: &PgPool,
+) -> VortoResult<Vec<WordDefinition>> {
+    let mut definitions_by_word =
+        definitions_with_examples_by_word(&vec![word_id], pool, None).await?;
+
+    VortoResult::Ok(definitions_by_word.remove(&word_id).unwrap_or_default())
+}
+
+// Same for many words at once, words without definitions are left out
+async fn definitions_with_examples_by_word(
+    word_ids: &Vec<i32>,
+    pool: &PgPool,
+    mut tx: Option<&mut Transaction<'_, Postgres>>,
+) -> VortoResult<HashMap<i32, Vec<WordDefinition>>> {
+    let word_definitions =
+        db::word_definition::get_by_word_ids(word_ids, pool, tx.as_deref_mut()).await?;
+    let definition_ids = word_definitions.iter().map(|wd| wd.id).collect();
+
+    let mut examples_by_definition: HashMap<i32, Vec<String>> = HashMap::new();
+    for (definition_id, example) in
+        db::word_definition_example::get_by_word_definition_ids(&definition_ids, pool, tx).await?
+    {
+        examples_by_definition
+            .entry(definition_id)
+            .or_default()
+            .push(example);
+    }
+
+    let mut definitions_by_word: HashMap<i32, Vec<WordDefinition>> = HashMap::new();
+    for wd in word_definitions {
+        definitions_by_word
+            .entry(wd.word_id)
+            .or_default()
+            .push(WordDefinition {
+                examples: examples_by_definition.remove(&wd.id).unwrap_or_default(),
+                ..wd
+            });
+    }
+
+    VortoResult::Ok(definitions_by_word)
+}
+
+pub async fn record_revision(
+    word_id: i32,
+    user_id: Option<i32>,
+    action: WordRevisionAction,
+    before: Option<&WordSnapshot>,
+    after: &WordSnapshot,
+    pool: &PgPool,
+    tx: &mut Transaction<'_, Postgres>,
+) -> VortoResult<()> {
+    let word_revision =
+        domain::word_revision::new(word_id, user_id, &action, before, after, &Utc::now())?;
+    db::word_revision::insert(&word_revision, pool, Some(tx)).await?;
+
+    VortoResult::Ok(())
+}
+
+// The unique body index also covers archived words, they have to be restored instead
+pub async fn create(req: &CreateWordRequest, user_id: i32, pool: &PgPool) -> VortoResult<i32> {
+    let word = domain::word::create(
+        &req.body,
+        req.status,
         req.difficulty,
-        req.timestamp,
-        &req.definitions,
+        req.part_of_speech.as_ref(),
+        req.gender.as_ref(),
+        &req.tags,
         &Utc::now(),
     )?;
 
+    let normalized_body = domain::word::normalize_body(&word.body);
+    if let Some(existing) = db::word::get_by_normalized_body(&normalized_body, pool).await? {
+        return VortoResult::Err(VortoError::new(
+            VortoErrorCode::Validation,
+            format!(
+                "Word '{}' already exists with status '{}'",
+                existing.body, existing.status
+            ),
+        ));
+    }
+
+    let mut tx = pool.begin().await?;
+    let word_id = db::word::insert(&word, pool, Some(&mut tx)).await?;
+    let after = domain::word_revision::snapshot(&word, &vec![]);
+    record_revision(
+        word_id,
+        Some(user_id),
+        WordRevisionAction::Create,
+        None,
+        &after,
+        pool,
+        &mut tx,
+    )
+    .await?;
+    tx.commit().await?;
+
+    VortoResult::Ok(word_id)
+}
+
+// Played words are referenced by word_results, so they are never removed
+pub async fn archive(req: &ArchiveWordRequest, user_id: i32, pool: &PgPool) -> VortoResult<()> {
+    let word = db::word::get_by_id(req.id, pool).await?;
+    let word_definitions = definitions_with_examples(word.id, pool).await?;
+    let new_word = domain::word::archive(&word, req.timestamp, &Utc::now())?;
+
+    let before = domain::word_revision::snapshot(&word, &word_definitions);
+    let after = domain::word_revision::snapshot(&new_word, &word_definitions);
+
     let mut tx = pool.begin().await?;
     db::word::update(&new_word, pool, Some(&mut tx)).await?;
+    record_revision(
+        word.id,
+        Some(user_id),
+        WordRevisionAction::Archive,
+        Some(&before),
+        &after,
+        pool,
+        &mut tx,
+    )
+    .await?;
+    tx.commit().await?;
+
+    VortoResult::Ok(())
+}
+
+pub async fn update(req: UpdateWordRequest, user_id: i32, pool: &PgPool) -> VortoResult<()> {
+    let word = db::word::get_by_id(req.id, pool).await?;
+    let word_definitions = definitions_with_examples(word.id, pool).await?;
+
+    let word_update = WordUpdate {
+        status: &req.status,
+        difficulty: req.difficulty,
+        part_of_speech: req.part_of_speech.as_ref().map(Option::as_ref),
+        gender: req.gender.as_ref().map(Option::as_ref),
+        definitions: &req.definitions,
+    };
+    let (new_word, new_word_definitions) =
+        domain::word::update(&word, &word_update, req.timestamp, &Utc::now())?;
+
+    let before = domain::word_revision::snapshot(&word, &word_definitions);
+    let after = domain::word_revision::snapshot(&new_word, &new_word_definitions);
+
+    let mut tx = pool.begin().await?;
+    db::word::update(&new_word, pool, Some(&mut tx)).await?;
+
+    replace_definitions(word.id, &new_word_definitions, pool, &mut tx).await?;
+    record_revision(
+        word.id,
+        Some(user_id),
+        WordRevisionAction::Update,
+        Some(&before),
+        &after,
+        pool,
+        &mut tx,
+    )
+    .await?;
+    tx.commit().await?;
 
-    db::word_definition::delete_by_word_id(word.id, pool, Some(&mut tx)).await?;
-    for wd in new_word_definitions {
-        db::word_definition::insert(&wd, pool, Some(&mut tx)).await?;
+    VortoResult::Ok(())
+}
+
+// Selected words are locked for the whole edit. Words failing their checks are skipped
+// and reported, the rest are saved together
+pub async fn bulk_edit(
+    req: &BulkEditWordsRequest,
+    user_id: i32,
+    pool: &PgPool,
+) -> VortoResult<Vec<BulkEditOutcomeView>> {
+    let timestamps: HashMap<i32, i64> = match &req.filter {
+        Some(_) if !req.words.is_empty() => {
+            return VortoResult::Err(VortoError::new(
+                VortoErrorCode::Validation,
+                "Either words or a filter should be given".to_owned(),
+            ));
+        }
+        Some(filter) => vec_to_map(
+            &filter_words(filter, pool).await?,
+            |w| w.id,
+            |w| w.timestamp,
+        ),
+        None => vec_to_map(&req.words, |w| w.id, |w| w.timestamp),
+    };
+    let ids = timestamps.keys().cloned().sorted().collect::<Vec<_>>();
+    let now = Utc::now();
+
+    let mut tx = pool.begin().await?;
+    let words = db::word::lock_by_ids(&ids, &mut tx).await?;
+    let words_by_id = vec_to_map(&words, |w| w.id, |w| w.clone());
+    // Read once for the revision snapshots of all words, activation works on them too
+    let mut definitions_by_word =
+        definitions_with_examples_by_word(&ids, pool, Some(&mut tx)).await?;
+
+    let mut outcomes = vec![];
+    for id in ids {
+        let word_definitions = definitions_by_word.remove(&id).unwrap_or_default();
+        let result = match words_by_id.get(&id) {
+            Some(word) => {
+                domain::word::bulk_edit(word, &word_definitions, &req.action, timestamps[&id], &now)
+            }
+            None => VortoResult::Err(VortoError::new(
+                VortoErrorCode::NotFound,
+                format!("Word {} not found", id),
+            )),
+        };
+
+        let outcome = match result {
+            VortoResult::Ok((new_word, new_word_definitions)) => {
+                db::word::update(&new_word, pool, Some(&mut tx)).await?;
+                merge_definitions(&new_word_definitions, pool, &mut tx).await?;
+
+                let after_definitions = word_definitions
+                    .iter()
+                    .map(|wd| {
+                        new_word_definitions
+                            .iter()
+                            .find(|new_wd| new_wd.id == wd.id)
+                            .unwrap_or(wd)
+                            .clone()
+                    })
+                    .collect();
+                let before = domain::word_revision::snapshot(&words_by_id[&id], &word_definitions);
+                let after = domain::word_revision::snapshot(&new_word, &after_definitions);
+                record_revision(
+                    id,
+                    Some(user_id),
+                    WordRevisionAction::BulkEdit,
+                    Some(&before),
+                    &after,
+                    pool,
+                    &mut tx,
+                )
+                .await?;
+
+                BulkEditOutcomeView {
+                    id,
+                    is_updated: true,
+                    timestamp: Some(new_word.timestamp),
+                    error_code: None,
+                    error: None,
+                }
+            }
+            VortoResult::Err(e) => BulkEditOutcomeView {
+                id,
+                is_updated: false,
+                timestamp: None,
+                error_code: Some(e.code),
+                error: Some(e.message),
+            },
+        };
+        outcomes.push(outcome);
     }
     tx.commit().await?;
 
+    VortoResult::Ok(outcomes)
+}
+
+// Reviewed upstream changes leave the queue
+pub async fn resolve_upstream_changes(
+    req: &ResolveUpstreamChangesRequest,
+    user_id: i32,
+    pool: &PgPool,
+) -> VortoResult<()> {
+    let word = db::word::get_by_id(req.id, pool).await?;
+    let word_definitions = definitions_with_examples(word.id, pool).await?;
+    let new_word = domain::word::resolve_upstream_changes(&word, req.timestamp, &Utc::now())?;
+
+    let before = domain::word_revision::snapshot(&word, &word_definitions);
+    let after = domain::word_revision::snapshot(&new_word, &word_definitions);
+
+    let mut tx = pool.begin().await?;
+    db::word::update(&new_word, pool, Some(&mut tx)).await?;
+    record_revision(
+        word.id,
+        Some(user_id),
+        WordRevisionAction::ResolveUpstreamChanges,
+        Some(&before),
+        &after,
+        pool,
+        &mut tx,
+    )
+    .await?;
+    tx.commit().await?;
+
+    VortoResult::Ok(())
+}
+
+fn word_revision_view(word_revision: &WordRevision) -> VortoResult<WordRevisionView> {
+    let action = match WordRevisionAction::from_str(&word_revision.action) {
+        Ok(action) => action,
+        Err(_) => {
+            return VortoResult::Err(VortoError::new(
+                VortoErrorCode::Validation,
+                format!("Unknown revision action '{}'", word_revision.action),
+            ))
+        }
+    };
+    let before = match &word_revision.before {
+        Some(before) => Some(serde_json::from_str::<WordSnapshot>(before)?),
+        None => None,
+    };
+
+    VortoResult::Ok(WordRevisionView {
+        id: word_revision.id,
+        user_id: word_revision.user_id,
+        action,
+        before,
+        after: serde_json::from_str(&word_revision.after)?,
+        created_at: word_revision.created_at,
+    })
+}
+
+pub async fn history(id: i32, pool: &PgPool) -> VortoResult<Vec<WordRevisionView>> {
+    let mut views = vec![];
+    for word_revision in db::word_revision::get_by_word_id(id, pool).await? {
+        views.push(word_revision_view(&word_revision)?);
+    }
+
+    VortoResult::Ok(views)
+}
+
+pub async fn revert(req: &RevertWordRequest, user_id: i32, pool: &PgPool) -> VortoResult<()> {
+    let word = db::word::get_by_id(req.id, pool).await?;
+    let word_definitions = definitions_with_examples(word.id, pool).await?;
+    let word_revision = db::word_revision::get_by_id(req.revision_id, pool).await?;
+
+    let snapshot = domain::word_revision::restorable_snapshot(&word_revision, word.id)?;
+    let (new_word, new_word_definitions) =
+        domain::word::revert(&word, &snapshot, req.timestamp, &Utc::now())?;
+
+    let before = domain::word_revision::snapshot(&word, &word_definitions);
+    let after = domain::word_revision::snapshot(&new_word, &new_word_definitions);
+
+    let mut tx = pool.begin().await?;
+    db::word::update(&new_word, pool, Some(&mut tx)).await?;
+    replace_definitions(word.id, &new_word_definitions, pool, &mut tx).await?;
+    record_revision(
+        word.id,
+        Some(user_id),
+        WordRevisionAction::Revert,
+        Some(&before),
+        &after,
+        pool,
+        &mut tx,
+    )
+    .await?;
+    tx.commit().await?;
+
     VortoResult::Ok(())
 }
 
 pub async fn word_stats(pool: &PgPool) -> VortoResult<WordStats> {
     db::word::word_stats(pool).await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(text: &str, text_mode: &str, ignore_yo: bool) -> WordFilter {
+        serde_json::from_value(serde_json::json!({
+            "text": text,
+            "text_mode": text_mode,
+            "ignore_yo": ignore_yo,
+            "statuses": [],
+            "load_statuses": [],
+            "difficulties": [],
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn highlights_prefix() {
+        assert_eq!(
+            body_highlights("Москва", &filter("мос", "prefix", false)),
+            vec!["<b>Мос</b>ква"]
+        );
+    }
+
+    #[test]
+    fn escapes_highlighted_body() {
+        assert_eq!(
+            body_highlights("a<b>&c", &filter("a<", "prefix", false)),
+            vec!["<b>a&lt;</b>b&gt;&amp;c"]
+        );
+    }
+
+    #[test]
+    fn highlights_fuzzy_shared_trigrams() {
+        assert_eq!(
+            body_highlights("Белый медведь", &filter("медвед", "fuzzy", false)),
+            vec!["Белый <b>медвед</b>ь"]
+        );
+    }
+
+    #[test]
+    fn highlights_fuzzy_with_yo_folded() {
+        assert_eq!(
+            body_highlights("ёж", &filter("еж", "fuzzy", true)),
+            vec!["<b>ёж</b>"]
+        );
+        assert_eq!(
+            body_highlights("ёж", &filter("еж", "fuzzy", false)),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn no_highlights_for_full_text() {
+        assert!(body_highlights("кот", &filter("кот", "full_text", false)).is_empty());
+    }
+}